@@ -0,0 +1,65 @@
+use std::path::PathBuf;
+
+/// Command line options understood by the agent.
+#[derive(Debug, Default)]
+pub struct AgentArgs {
+    /// `--config <path>`: policy configuration. Built-in rules are used when absent.
+    pub config: Option<PathBuf>,
+    /// `--exam <id>`: selects the policy profile that lists this exam.
+    pub exam: Option<String>,
+    /// `--explain`: report which rule, field and span flagged each violation.
+    pub explain: bool,
+    /// `--save-snapshot <path>`: write the pre-flight snapshot to this file.
+    pub save_snapshot: Option<PathBuf>,
+    /// `--replay <path>`: evaluate a saved snapshot instead of probing this
+    /// machine, report the verdict and exit without launching the browser.
+    pub replay: Option<PathBuf>,
+    /// `--fixture <path>`: probe a JSON fixture instead of this machine,
+    /// for demos and tests.
+    pub fixture: Option<PathBuf>,
+}
+
+impl AgentArgs {
+    pub fn parse() -> Result<Self, String> {
+        Self::parse_from(std::env::args().skip(1))
+    }
+
+    pub fn parse_from(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = AgentArgs::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--config" => {
+                    let value = args.next().ok_or("--config requires a path")?;
+                    parsed.config = Some(PathBuf::from(value));
+                }
+                "--explain" => parsed.explain = true,
+                "--exam" => {
+                    let value = args.next().ok_or("--exam requires an exam id")?;
+                    parsed.exam = Some(value);
+                }
+                "--save-snapshot" => {
+                    let value = args.next().ok_or("--save-snapshot requires a path")?;
+                    parsed.save_snapshot = Some(PathBuf::from(value));
+                }
+                "--replay" => {
+                    let value = args.next().ok_or("--replay requires a snapshot path")?;
+                    parsed.replay = Some(PathBuf::from(value));
+                }
+                "--fixture" => {
+                    let value = args.next().ok_or("--fixture requires a path")?;
+                    parsed.fixture = Some(PathBuf::from(value));
+                }
+                other => return Err(format!("unknown argument: {}", other)),
+            }
+        }
+        if parsed.fixture.is_some() && parsed.replay.is_some() {
+            return Err("--fixture and --replay cannot be combined".to_string());
+        }
+        // Profiles, and the exams they list, are defined in the config.
+        if parsed.exam.is_some() && parsed.config.is_none() {
+            return Err("--exam requires --config".to_string());
+        }
+        Ok(parsed)
+    }
+}
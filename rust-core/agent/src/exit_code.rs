@@ -0,0 +1,13 @@
+//! Process exit codes. Launcher scripts and the server key off these values,
+//! so existing codes must not be renumbered.
+
+/// The session ran and the student closed the window.
+pub const OK: i32 = 0;
+/// Pre-flight found violations in a `block` category; the browser was not launched.
+pub const PREFLIGHT_BLOCKED: i32 = 1;
+/// Invalid command line or policy configuration.
+pub const CONFIG_ERROR: i32 = 2;
+/// A runtime offense in a `block` category reached `terminate_after`
+/// strikes and ended the session. With `kill_processes`, a process offense
+/// only does when an offending process could not be stopped.
+pub const SESSION_TERMINATED: i32 = 3;
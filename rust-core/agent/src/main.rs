@@ -1,12 +1,20 @@
-use core::PolicyEngine;
-use platform_common::{ProcessScanner, SystemProfiler};
+mod args;
+mod exit_code;
+
+use args::AgentArgs;
+use core::{
+    unix_now, Category, Config, ConfigError, EscalationStep, PolicyEngine, RiskOutcome, Verdict, ViolationGroup,
+    ViolationLog, BASE_PROFILE,
+};
+use platform_common::{
+    Blocker, Deadlines, FixtureProfiler, ProcessScanner, Support, SystemProfiler, SystemSnapshot,
+};
 use tao::{
     event::{Event, WindowEvent},
     event_loop::{ControlFlow, EventLoop, EventLoopBuilder},
     window::WindowBuilder,
 };
 use wry::WebViewBuilder;
-use arboard::Clipboard;
 use pqc_kyber::{keypair, encapsulate};
 use aes_gcm::{
     aead::{Aead, KeyInit},
@@ -14,11 +22,24 @@ use aes_gcm::{
 };
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 use rand::rngs::OsRng;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Shown in the assessment window when a runtime offense reaches `warn`.
+const STUDENT_WARNING_SCRIPT: &str =
+    "alert('Warning: close the forbidden application and return to the assessment.');";
 
 #[derive(Debug)]
 enum AgentEvent {
+    /// Encrypted report of an offense that escalated to `terminate`; ends the session.
     Violation(String),
+    /// Encrypted report of an offense that escalated to `notify_proctor`.
+    ProctorAlert(String),
+    /// Encrypted report of an offense at the `warn` step; the student is
+    /// warned in the assessment window.
+    Warning(String),
+    /// Encrypted report of new findings; the session continues.
+    Report(String),
 }
 
 #[cfg(target_os = "windows")]
@@ -35,6 +56,51 @@ use platform_macos::MacProfiler as PlatformProfiler;
 #[cfg(target_os = "linux")]
 use platform_linux::LinuxProfiler as PlatformProfiler;
 
+#[cfg(target_os = "windows")]
+use platform_win::WindowsBlocker as PlatformBlocker;
+#[cfg(target_os = "macos")]
+use platform_macos::MacBlocker as PlatformBlocker;
+#[cfg(target_os = "linux")]
+use platform_linux::LinuxBlocker as PlatformBlocker;
+
+#[cfg(target_os = "linux")]
+fn platform_scanner() -> PlatformScanner {
+    PlatformScanner::default()
+}
+#[cfg(not(target_os = "linux"))]
+fn platform_scanner() -> PlatformScanner {
+    PlatformScanner
+}
+
+#[cfg(target_os = "linux")]
+fn platform_profiler() -> PlatformProfiler {
+    PlatformProfiler::default()
+}
+#[cfg(not(target_os = "linux"))]
+fn platform_profiler() -> PlatformProfiler {
+    PlatformProfiler
+}
+
+#[cfg(target_os = "linux")]
+fn platform_blocker() -> PlatformBlocker {
+    PlatformBlocker::default()
+}
+#[cfg(not(target_os = "linux"))]
+fn platform_blocker() -> PlatformBlocker {
+    PlatformBlocker
+}
+
+/// The profiler and scanner the agent probes with: the platform's, or the
+/// `--fixture` file's.
+fn probes(
+    fixture: Option<&FixtureProfiler>,
+) -> (Arc<dyn SystemProfiler + Send + Sync>, Arc<dyn ProcessScanner + Send + Sync>) {
+    match fixture {
+        Some(fixture) => (Arc::new(fixture.clone()), Arc::new(fixture.clone())),
+        None => (Arc::new(platform_profiler()), Arc::new(platform_scanner())),
+    }
+}
+
 fn encrypt_violation(msg: &str, server_pk: &[u8]) -> String {
     let mut rng = OsRng;
     let (kyber_ct, shared_secret) = encapsulate(server_pk, &mut rng).unwrap();
@@ -61,8 +127,152 @@ fn encrypt_violation(msg: &str, server_pk: &[u8]) -> String {
     BASE64.encode(combined)
 }
 
+/// Loads the config and picks the profile that lists `exam`, or the base
+/// policy without one, with the pre-flight deadlines.
+fn load_profile(path: &Path, exam: Option<&str>) -> Result<(PolicyEngine, Deadlines), ConfigError> {
+    let config = Config::load(path)?;
+    let deadlines = config.preflight.unwrap_or_default();
+    let policy = config.policy.unwrap_or_default();
+    let profile = match exam {
+        Some(exam) => policy.profile_for_exam(exam)?,
+        None => BASE_PROFILE,
+    };
+    Ok((PolicyEngine::from_profile(&policy, profile)?, deadlines))
+}
+
+fn load_policy(args: &AgentArgs) -> (PolicyEngine, Deadlines) {
+    let Some(path) = &args.config else {
+        println!("No policy config given, using built-in rules.");
+        return (PolicyEngine::new(), Deadlines::default());
+    };
+    match load_profile(path, args.exam.as_deref()) {
+        Ok((engine, deadlines)) => {
+            println!("Policy loaded from {} (profile: {})", path.display(), engine.profile());
+            (engine, deadlines)
+        }
+        Err(e) => {
+            eprintln!("Invalid policy config {}: {}", path.display(), e);
+            std::process::exit(exit_code::CONFIG_ERROR);
+        }
+    }
+}
+
+/// Prints the explain-mode trace of a group, if the engine recorded one.
+fn print_trace(group: &ViolationGroup) {
+    if let Some(trace) = &group.violation.trace {
+        println!("      why: {} [{}]", trace, group.violation.rule_id);
+    }
+}
+
+fn print_groups(verdict: &Verdict) {
+    for group in &verdict.blocking {
+        println!(" - {}", group);
+        print_trace(group);
+    }
+    for group in &verdict.audited {
+        println!(" - [audit] {}", group);
+        print_trace(group);
+    }
+    for suppression in &verdict.suppressed {
+        println!(" - [whitelisted by {}] {}", suppression.allow_rule_id, suppression.group);
+        print_trace(&suppression.group);
+        if let Some(allowed_by) = &suppression.allowed_by {
+            println!("      allowed: {}", allowed_by);
+        }
+    }
+}
+
+/// The pre-flight verdict with what it was judged on: where and when the
+/// snapshot was taken, what the platform can probe, how each probe went and
+/// how long the pre-flight took.
+fn preflight_report(verdict: &Verdict, snapshot: &SystemSnapshot) -> serde_json::Value {
+    serde_json::json!({
+        "verdict": verdict,
+        "snapshot": {
+            "taken_at": snapshot.taken_at,
+            "platform": snapshot.platform,
+            "agent_version": snapshot.agent_version,
+            "capabilities": snapshot.capabilities,
+            "probes": snapshot.probes,
+            "timings": snapshot.timings,
+        },
+    })
+}
+
+/// Prints the inventory of a snapshot, the probes the platform does not
+/// fully support and the probes that failed.
+fn print_snapshot(snapshot: &SystemSnapshot) {
+    println!("Antivirus Software Found: {}", snapshot.antivirus.len());
+    for av in &snapshot.antivirus {
+        println!(" - {} (Enabled: {})", av.name, av.is_enabled);
+    }
+    println!("Displays Found: {}", snapshot.displays.len());
+    for d in &snapshot.displays {
+        let placement = if d.is_internal { "internal" } else { "external" };
+        let state = if d.is_enabled { "enabled" } else { "disabled" };
+        print!(" - {} (ID: {}) [{}, {}, {}]", d.name, d.device_id, d.connector, placement, state);
+        match d.flag {
+            Some(flag) => println!(" FLAGGED: {}", flag),
+            None => println!(),
+        }
+    }
+    println!("Browsers Found: {}", snapshot.browsers.len());
+    for browser in &snapshot.browsers {
+        println!(" - {} (Profiles: {})", browser.name, browser.profiles.len());
+    }
+    println!("Extensions Found: {}", snapshot.extensions.len());
+    for ext in &snapshot.extensions {
+        let state = if ext.is_enabled { "enabled" } else { "disabled" };
+        println!(
+            " - {} ({}) [{} {}] {}, {}",
+            ext.name, ext.version, ext.family, ext.id, ext.install_source, state
+        );
+    }
+    if let Some(detection) = &snapshot.vm {
+        println!("Virtual Machine: {} (confidence {}%)", detection.is_vm(), detection.confidence);
+        for signal in &detection.signals {
+            println!(" - {}: {}", signal.source, signal.value);
+        }
+    }
+    println!("Processes Scanned: {}", snapshot.processes.len());
+    println!("Network Adapters: {}", snapshot.network_adapters.len());
+    for ad in &snapshot.network_adapters {
+        let state = if ad.is_up { "up" } else { "down" };
+        println!(" - {} ({}) [{}, {}]", ad.name, ad.description, ad.kind, state);
+    }
+    println!("Hosts File Entries: {}", snapshot.hosts.len());
+    if let Some(title) = &snapshot.active_window {
+        println!("Active Window: {}", title);
+    }
+    for (probe, support) in snapshot.capabilities.iter().filter(|(_, s)| *s != Support::Supported) {
+        println!("Probe {} is {} on this platform", probe, support);
+    }
+    for (probe, status) in snapshot.failures() {
+        println!("Probe {} failed: {}", probe, status);
+    }
+    println!("Pre-flight took {} ms", snapshot.timings.total_ms);
+}
+
+fn print_risk(verdict: &Verdict) {
+    println!("[*] Policy profile: {}", verdict.profile);
+    println!("[*] Risk score: {} ({:?})", verdict.risk.score, verdict.risk.outcome);
+    for contribution in &verdict.risk.contributions {
+        println!("    {:>4}  {}", contribution.weight, contribution.rule_id);
+    }
+}
+
 fn main() {
     println!("Starting OLL Assessment Driver Agent...");
+    let args = match AgentArgs::parse() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(exit_code::CONFIG_ERROR);
+        }
+    };
+    let (mut policy, deadlines) = load_policy(&args);
+    policy.set_explain(args.explain);
+    let policy = Arc::new(policy);
     println!("Initializing Quantum-Resistant Cryptography...");
     
     // Simulate Server Key Exchange (In production, this PK would be hardcoded or fetched)
@@ -71,174 +281,216 @@ fn main() {
     let server_pk = keys.public;
     println!("Secure Channel Established (Kyber-768).");
 
-    let violations = std::thread::spawn(move || {
-        // System Profiling
-        let profiler = PlatformProfiler;
-        
-        match profiler.list_antivirus() {
-            Ok(avs) => {
-                println!("Antivirus Software Found: {}", avs.len());
-                for av in avs {
-                    println!(" - {} (Enabled: {})", av.name, av.is_enabled);
-                }
-            }
-            Err(e) => println!("Error listing antivirus: {}", e),
+    // Loaded once so the pre-flight and runtime checks share its schedule.
+    let fixture = args.fixture.as_ref().map(|path| match FixtureProfiler::load(path) {
+        Ok(fixture) => {
+            println!("Probing fixture {} instead of this machine.", path.display());
+            fixture
         }
-
-        let mut displays = Vec::new();
-        match profiler.list_displays() {
-            Ok(ds) => {
-                println!("Displays Found: {}", ds.len());
-                for d in &ds {
-                    println!(" - {} (ID: {})", d.name, d.device_id);
-                }
-                displays = ds;
-            }
-            Err(e) => println!("Error listing displays: {}", e),
+        Err(e) => {
+            eprintln!("Invalid fixture: {}", e);
+            std::process::exit(exit_code::CONFIG_ERROR);
         }
+    });
 
-        let mut all_browsers = Vec::new();
-        let mut all_extensions = Vec::new();
-
-        match profiler.list_browsers() {
-            Ok(browsers) => {
-                println!("Browsers Found: {}", browsers.len());
-                for browser in browsers {
-                    println!(" - {} (Profiles: {})", browser.name, browser.profiles.len());
-                    match profiler.list_extensions(&browser) {
-                        Ok(exts) => {
-                            if !exts.is_empty() {
-                                println!("   Extensions: {}", exts.len());
-                                for ext in &exts {
-                                    println!("    * {} ({}) [{}]", ext.name, ext.version, ext.id);
-                                }
-                                all_extensions.extend(exts);
-                            }
-                        }
-                        Err(e) => println!("   Error listing extensions: {}", e),
-                    }
-                    all_browsers.push(browser);
-                }
+    let snapshot = match &args.replay {
+        Some(path) => match SystemSnapshot::load(path) {
+            Ok(snapshot) => {
+                println!(
+                    "Replaying snapshot {} (taken at {} on {}, agent {})",
+                    path.display(),
+                    snapshot.taken_at,
+                    snapshot.platform,
+                    snapshot.agent_version
+                );
+                snapshot
             }
-            Err(e) => println!("Error listing browsers: {}", e),
-        }
-
-        let mut is_vm = false;
-        match profiler.check_vm() {
-            Ok(vm) => {
-                is_vm = vm;
-                println!("Virtual Machine: {}", vm);
+            Err(e) => {
+                eprintln!("Cannot replay: {}", e);
+                std::process::exit(exit_code::CONFIG_ERROR);
             }
-            Err(e) => println!("Error checking VM: {}", e),
+        },
+        None => {
+            let (profiler, scanner) = probes(fixture.as_ref());
+            SystemSnapshot::capture_within(profiler, scanner, env!("CARGO_PKG_VERSION"), &deadlines)
         }
-
-        // Process Scanning
-        let scanner = PlatformScanner;
-        let mut processes = Vec::new();
-        match scanner.scan() {
-            Ok(procs) => {
-                println!("Processes Scanned: {}", procs.len());
-                processes = procs;
-            }
-            Err(e) => println!("Error scanning processes: {}", e),
-        }
-
-        // Network Adapters
-        let mut adapters = Vec::new();
-        match profiler.list_network_adapters() {
-            Ok(ads) => {
-                println!("Network Adapters (Connected): {}", ads.len());
-                for ad in &ads {
-                    println!(" - {} ({})", ad.name, ad.description);
-                }
-                adapters = ads;
-            }
-            Err(e) => println!("Error listing network adapters: {}", e),
+    };
+    print_snapshot(&snapshot);
+    if let Some(path) = &args.save_snapshot {
+        match snapshot.save(path) {
+            Ok(()) => println!("Snapshot saved to {}", path.display()),
+            Err(e) => println!("Error saving snapshot: {}", e),
         }
+    }
+    println!("Evaluating policy...");
+    let verdict = policy.evaluate_snapshot(&snapshot);
 
-        // Hosts File
-        let mut hosts = Vec::new();
-        match profiler.parse_hosts_file() {
-            Ok(entries) => {
-                println!("Hosts File Entries: {}", entries.len());
-                hosts = entries;
-            }
-            Err(e) => println!("Error parsing hosts file: {}", e),
+    if !verdict.is_empty() {
+        if verdict.is_blocked() {
+            println!("\n[!] CRITICAL: Security violations detected.");
+        } else if verdict.risk.outcome == RiskOutcome::Pass {
+            println!("\n[*] Audit: policy violations recorded.");
+        } else {
+            println!("\n[!] Policy violations detected.");
         }
-
-        // Active Window
-        let mut active_window = String::new();
-        match profiler.get_active_window() {
-            Ok(title) => {
-                println!("Active Window: {}", title);
-                active_window = title;
-            }
-            Err(e) => println!("Error getting active window: {}", e),
+        print_risk(&verdict);
+        if args.replay.is_none() {
+            println!("[*] Encrypting Violation Report...");
         }
+        print_groups(&verdict);
+    }
+    if args.replay.is_some() {
+        // A replay only re-judges the snapshot: nothing is sent and the
+        // browser is not launched.
+        println!("{}", preflight_report(&verdict, &snapshot));
+        let code = if verdict.is_blocked() { exit_code::PREFLIGHT_BLOCKED } else { exit_code::OK };
+        std::process::exit(code);
+    }
 
-        let policy_engine = PolicyEngine::new();
-        policy_engine.evaluate(
-            &all_browsers,
-            &all_extensions,
-            &displays,
-            &processes,
-            &adapters,
-            &hosts,
-            &active_window,
-            is_vm,
-        )
-    }).join().unwrap();
-
-    if !violations.is_empty() {
-        println!("\n[!] CRITICAL: Security violations detected.");
-        println!("[*] Encrypting Violation Report...");
-        
-        let report = violations.join("\n");
+    if !verdict.is_empty() {
+        let report = preflight_report(&verdict, &snapshot).to_string();
         let encrypted_report = encrypt_violation(&report, &server_pk);
-        
+
         println!("[*] Sending Quantum-Encrypted Violation Report to OLL Server...");
         println!("[*] Payload: {}", encrypted_report);
-        println!("[!] The Assessment Browser cannot be launched until these issues are resolved.");
-        println!("[!] Please disable forbidden software, disconnect extra monitors, and retry.");
-        // println!("[!] Exiting...");
-        // std::process::exit(1);
-        println!("[!] WARNING: PROCEEDING WITH VIOLATIONS FOR DEMONSTRATION PURPOSES.");
+        if verdict.is_blocked() {
+            println!("[!] The Assessment Browser cannot be launched until these issues are resolved.");
+            println!("[!] Please disable forbidden software, disconnect extra monitors, and retry.");
+            println!("[!] Exiting...");
+            std::process::exit(exit_code::PREFLIGHT_BLOCKED);
+        }
+        match verdict.risk.outcome {
+            RiskOutcome::Review => println!("[!] This session has been flagged for proctor review."),
+            RiskOutcome::Warn => println!("[!] Warning: close the software listed above before starting."),
+            _ => {}
+        }
     }
 
     println!("Launching Secure Assessment Browser...");
-    let event_loop = EventLoopBuilder::<AgentEvent>::with_user_event().build();
+    let event_loop: EventLoop<AgentEvent> = EventLoopBuilder::with_user_event().build();
     let proxy = event_loop.create_proxy();
 
     // Start Monitoring Thread
-    let server_pk_clone = server_pk.clone();
-    
+    let runtime_policy = Arc::clone(&policy);
+    let mut session_risk = policy.session_risk();
+    session_risk.record(&verdict);
+    // Seeded with the pre-flight groups so subjects already reported there are
+    // not reported again on the first runtime check.
+    let mut violation_log = ViolationLog::new();
+    violation_log.record(&verdict);
+    let mut escalator = policy.escalator();
+
+    let (profiler, scanner) = probes(fixture.as_ref());
+    // Fixture processes are not running here; never signal their pids.
+    let blocker = fixture.is_none().then(platform_blocker);
     std::thread::spawn(move || {
-        let profiler = PlatformProfiler;
-        let scanner = PlatformScanner;
-        let policy = PolicyEngine::new();
-        let server_pk = server_pk_clone;
+        let policy = runtime_policy;
 
         loop {
             std::thread::sleep(std::time::Duration::from_secs(5));
-            
+
+            let mut violations = Vec::new();
+
             // Check Active Window
-            if let Ok(title) = profiler.get_active_window() {
-                if let Some(violation) = policy.check_active_window(&title) {
-                    let encrypted = encrypt_violation(&violation, &server_pk);
-                    let _ = proxy.send_event(AgentEvent::Violation(encrypted));
-                    break;
-                }
+            match profiler.get_active_window() {
+                Ok(title) => violations.extend(policy.check_active_window(&title)),
+                Err(e) => violations.extend(policy.check_probe(Category::Window, &e)),
             }
 
             // Check Processes (less frequent? 5s is fine)
-            if let Ok(procs) = scanner.scan() {
-                 let violations = policy.check_processes(&procs);
-                 if !violations.is_empty() {
-                     let msg = violations.join(", ");
-                     let encrypted = encrypt_violation(&msg, &server_pk);
-                     let _ = proxy.send_event(AgentEvent::Violation(encrypted));
-                     break;
-                 }
+            match scanner.scan() {
+                Ok(procs) => violations.extend(policy.check_processes(&procs)),
+                Err(e) => violations.extend(policy.check_probe(Category::Process, &e)),
+            }
+
+            let verdict = policy.decide(violations);
+            session_risk.record(&verdict);
+            // Only groups that are new or came back since the previous check
+            // are reported and strike: an offense that stays open counts once.
+            let fresh = violation_log.record(&verdict);
+            let escalations = escalator.record(&fresh, unix_now());
+            // With `kill_processes`, a process offense that reached terminate
+            // stops every offending process still running; the session only
+            // ends if one survives.
+            // All at once, so the loop waits for one grace period at most.
+            let to_kill = escalator.processes_to_kill(&verdict);
+            let blocked: Vec<_> = match &blocker {
+                Some(blocker) => to_kill
+                    .iter()
+                    .map(|process| (*process).clone())
+                    .zip(blocker.block_all(&to_kill))
+                    .collect(),
+                None => Vec::new(),
+            };
+            let processes_stopped = !blocked.is_empty() && blocked.iter().all(|(_, result)| result.is_gone());
+            let verdict = fresh;
+            let escalated: Vec<_> = escalations.into_iter().filter(|e| e.escalated).collect();
+            if verdict.is_empty() && escalated.is_empty() && blocked.is_empty() {
+                continue;
+            }
+            let session = session_risk.assessment();
+            // A session score that reaches terminate ends the session whatever
+            // the ladder says; stopped processes still count towards it.
+            let step = if session.outcome == RiskOutcome::Terminate {
+                Some(EscalationStep::Terminate)
+            } else {
+                escalated
+                    .iter()
+                    .filter(|e| !(processes_stopped && e.category == Category::Process))
+                    .map(|e| e.step)
+                    .max()
+            };
+            for group in &verdict.blocking {
+                println!("[!] {}", group);
+                print_trace(group);
+            }
+            for group in &verdict.audited {
+                println!("[audit] {}", group);
+                print_trace(group);
+            }
+            for suppression in &verdict.suppressed {
+                println!("[whitelisted by {}] {}", suppression.allow_rule_id, suppression.group);
+                print_trace(&suppression.group);
+                if let Some(allowed_by) = &suppression.allowed_by {
+                    println!("      allowed: {}", allowed_by);
+                }
+            }
+            for escalation in &escalated {
+                println!(
+                    "[!] {} strike {}: {:?}",
+                    escalation.category, escalation.strikes, escalation.step
+                );
+            }
+            for (process, result) in &blocked {
+                println!("[!] Stopping {} (pid {}): {:?}", process.name, process.pid, result);
+            }
+            println!("[*] Session risk score: {} ({:?})", session.score, session.outcome);
+            let blocked: Vec<_> = blocked
+                .iter()
+                .map(|(process, result)| serde_json::json!({ "process": process, "result": result }))
+                .collect();
+            let report = serde_json::json!({
+                "verdict": verdict,
+                "escalations": escalated,
+                "blocked": blocked,
+                "session_risk": session,
+            })
+            .to_string();
+            let encrypted = encrypt_violation(&report, &server_pk);
+            match step {
+                Some(EscalationStep::Terminate) => {
+                    let _ = proxy.send_event(AgentEvent::Violation(encrypted));
+                    break;
+                }
+                Some(EscalationStep::NotifyProctor) => {
+                    let _ = proxy.send_event(AgentEvent::ProctorAlert(encrypted));
+                }
+                Some(EscalationStep::Warn) => {
+                    let _ = proxy.send_event(AgentEvent::Warning(encrypted));
+                }
+                None => {
+                    let _ = proxy.send_event(AgentEvent::Report(encrypted));
+                }
             }
         }
     });
@@ -250,7 +502,7 @@ fn main() {
         .unwrap();
 
     #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
-    let _webview = WebViewBuilder::new()
+    let webview = WebViewBuilder::new()
         .with_url("https://ollacademy.com")
         .with_navigation_handler(|url: String| {
             // Robust parsing with url crate
@@ -301,12 +553,28 @@ fn main() {
                 println!("[*] Sending Quantum-Encrypted Telemetry to OLL Server...");
                 println!("[*] Payload: {}", encrypted_msg);
                 println!("[!] Terminating Assessment Session...");
-                *control_flow = ControlFlow::Exit;
+                *control_flow = ControlFlow::ExitWithCode(exit_code::SESSION_TERMINATED);
+            }
+            Event::UserEvent(AgentEvent::ProctorAlert(encrypted_msg)) => {
+                println!("\n[!] Repeated violation: notifying the proctor");
+                println!("[*] Sending Quantum-Encrypted Telemetry to OLL Server...");
+                println!("[*] Payload: {}", encrypted_msg);
+            }
+            Event::UserEvent(AgentEvent::Warning(encrypted_msg)) => {
+                println!("\n[!] Violation: warning the student");
+                println!("[*] Sending Quantum-Encrypted Telemetry to OLL Server...");
+                println!("[*] Payload: {}", encrypted_msg);
+                #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+                let _ = webview.evaluate_script(STUDENT_WARNING_SCRIPT);
+            }
+            Event::UserEvent(AgentEvent::Report(encrypted_msg)) => {
+                println!("[*] Sending Quantum-Encrypted Telemetry to OLL Server...");
+                println!("[*] Payload: {}", encrypted_msg);
             }
             Event::WindowEvent {
                 event: WindowEvent::CloseRequested,
                 ..
-            } => *control_flow = ControlFlow::Exit,
+            } => *control_flow = ControlFlow::ExitWithCode(exit_code::OK),
             _ => (),
         }
     });
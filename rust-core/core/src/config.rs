@@ -0,0 +1,568 @@
+use crate::escalation::EscalationPolicy;
+use crate::fields::fields_for;
+use crate::matcher::{MatchMode, Matcher};
+use crate::probe::{validate_required, ProbePolicy};
+use crate::profile::ProfileConfig;
+use crate::risk::RiskThresholds;
+use crate::rules::{AllowRule, Rule, RuleSet};
+use crate::violation::{Category, Severity};
+use platform_common::{Deadlines, Probe, Support};
+use serde::de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Top level of `config.yaml`. Every section is optional; a missing section
+/// falls back to the built-in defaults.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub policy: Option<PolicyConfig>,
+    pub reporting: Option<ReportingConfig>,
+    /// Per-probe deadlines for the pre-flight checks.
+    pub preflight: Option<Deadlines>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyConfig {
+    #[serde(default)]
+    pub mode: PolicyMode,
+    /// Overrides `mode` for individual categories, e.g. `window: audit`.
+    #[serde(default)]
+    pub category_modes: BTreeMap<Category, PolicyMode>,
+    #[serde(default)]
+    pub whitelist: Vec<AllowRule>,
+    #[serde(default)]
+    pub blacklist: Vec<Keyword>,
+    pub rules: Option<RulesConfig>,
+    #[serde(default)]
+    pub risk: RiskConfig,
+    /// Strike ladder for violations detected while the session runs.
+    #[serde(default)]
+    pub escalation: EscalationPolicy,
+    /// What a failed or unsupported probe means, by category. Probes left
+    /// out fail open.
+    #[serde(default)]
+    pub probes: BTreeMap<Category, ProbePolicy>,
+    /// The least support the platform must declare for a probe before an
+    /// exam may start, e.g. `vm: supported`.
+    #[serde(default)]
+    pub required_capabilities: BTreeMap<Probe, Support>,
+    /// Named per-assessment variants of this policy.
+    #[serde(default)]
+    pub profiles: BTreeMap<String, ProfileConfig>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RiskConfig {
+    #[serde(default)]
+    pub thresholds: RiskThresholds,
+    /// Weight overrides by rule id. Applies to built-in rules too, including
+    /// `display.multiple`, `display.suspicious` and `vm.detected`.
+    #[serde(default)]
+    pub weights: BTreeMap<String, u32>,
+}
+
+/// What happens to a violation once it is detected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PolicyMode {
+    /// Refuse to launch the assessment and end a running session.
+    #[default]
+    Block,
+    /// Record and report the violation, but never block.
+    Audit,
+    /// Skip enforcement; the category is still profiled.
+    Allow,
+}
+
+/// Per-category rule lists. A category that is left out keeps its
+/// built-in rules; an explicit empty list disables the category.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RulesConfig {
+    pub browsers: Option<Vec<RuleSpec>>,
+    pub extensions: Option<Vec<RuleSpec>>,
+    pub processes: Option<Vec<RuleSpec>>,
+    pub network_adapters: Option<Vec<RuleSpec>>,
+    pub hosts: Option<Vec<RuleSpec>>,
+    pub windows: Option<Vec<RuleSpec>>,
+    pub max_displays: Option<usize>,
+}
+
+/// A rule list entry: either a bare keyword, which becomes a single-keyword
+/// word-match rule with the category's default severity, or a full rule
+/// mapping. Keywords are compiled while parsing so a bad regex or glob is
+/// reported with its line number.
+#[derive(Debug, Clone)]
+pub enum RuleSpec {
+    Keyword(Matcher),
+    Rule {
+        id: String,
+        keywords: Vec<Matcher>,
+        severity: Option<Severity>,
+        weight: Option<u32>,
+        fields: Vec<String>,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleConfig {
+    id: String,
+    keywords: Vec<Keyword>,
+    severity: Option<Severity>,
+    weight: Option<u32>,
+    #[serde(default)]
+    fields: Vec<String>,
+    #[serde(rename = "match", default)]
+    mode: MatchMode,
+}
+
+fn compile<E: de::Error>(mode: MatchMode, keyword: &Keyword) -> Result<Matcher, E> {
+    Matcher::new(mode, keyword.as_str()).map_err(E::custom)
+}
+
+impl<'de> Deserialize<'de> for RuleSpec {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RuleSpecVisitor;
+
+        impl<'de> Visitor<'de> for RuleSpecVisitor {
+            type Value = RuleSpec;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a keyword string or a rule with `id` and `keywords`")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<RuleSpec, E> {
+                let keyword = Keyword::try_from(value.to_string()).map_err(E::custom)?;
+                Ok(RuleSpec::Keyword(compile(MatchMode::default(), &keyword)?))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<RuleSpec, A::Error> {
+                let rule = RuleConfig::deserialize(de::value::MapAccessDeserializer::new(map))?;
+                if rule.id.trim().is_empty() {
+                    return Err(de::Error::custom("rule id must not be empty"));
+                }
+                if rule.keywords.is_empty() {
+                    return Err(de::Error::custom(format!("rule `{}` has no keywords", rule.id)));
+                }
+                let keywords = rule
+                    .keywords
+                    .iter()
+                    .map(|keyword| compile(rule.mode, keyword))
+                    .collect::<Result<_, _>>()?;
+                Ok(RuleSpec::Rule {
+                    id: rule.id,
+                    keywords,
+                    severity: rule.severity,
+                    weight: rule.weight,
+                    fields: rule.fields,
+                })
+            }
+        }
+
+        deserializer.deserialize_any(RuleSpecVisitor)
+    }
+}
+
+impl RuleSpec {
+    pub fn to_rule(&self, category: Category) -> Rule {
+        match self {
+            RuleSpec::Keyword(keyword) => Rule {
+                id: format!("{}.{}", category, keyword.pattern().to_lowercase()),
+                keywords: vec![keyword.clone()],
+                severity: category.default_severity(),
+                weight: category.default_severity().default_weight(),
+                fields: Vec::new(),
+                when: Vec::new(),
+            },
+            RuleSpec::Rule {
+                id,
+                keywords,
+                severity,
+                weight,
+                fields,
+            } => {
+                let severity = severity.unwrap_or_else(|| category.default_severity());
+                Rule {
+                    id: id.clone(),
+                    keywords: keywords.clone(),
+                    severity,
+                    weight: weight.unwrap_or_else(|| severity.default_weight()),
+                    fields: fields.clone(),
+                    when: Vec::new(),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AllowConfig {
+    id: String,
+    process_name: Option<Keyword>,
+    exe_path: Option<Keyword>,
+    extension_id: Option<Keyword>,
+    window_title: Option<Keyword>,
+    #[serde(rename = "match", default)]
+    mode: MatchMode,
+}
+
+/// A whitelist entry is either a bare keyword, word-matched against every
+/// field, or a mapping that names the fields to match. Extension ids always
+/// match exactly.
+impl<'de> Deserialize<'de> for AllowRule {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct AllowRuleVisitor;
+
+        impl<'de> Visitor<'de> for AllowRuleVisitor {
+            type Value = AllowRule;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a keyword string or a whitelist entry with an `id`")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<AllowRule, E> {
+                let keyword = Keyword::try_from(value.to_string()).map_err(E::custom)?;
+                let matcher = Some(compile(MatchMode::default(), &keyword)?);
+                Ok(AllowRule {
+                    id: keyword.as_str().to_string(),
+                    process_name: matcher.clone(),
+                    exe_path: matcher.clone(),
+                    extension_id: Some(compile(MatchMode::Exact, &keyword)?),
+                    window_title: matcher,
+                })
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<AllowRule, A::Error> {
+                let entry = AllowConfig::deserialize(de::value::MapAccessDeserializer::new(map))?;
+                if entry.id.trim().is_empty() {
+                    return Err(de::Error::custom("whitelist id must not be empty"));
+                }
+                if entry.process_name.is_none()
+                    && entry.exe_path.is_none()
+                    && entry.extension_id.is_none()
+                    && entry.window_title.is_none()
+                {
+                    return Err(de::Error::custom(format!(
+                        "whitelist entry `{}` needs at least one of process_name, exe_path, extension_id, window_title",
+                        entry.id
+                    )));
+                }
+                let field = |mode, value: &Option<Keyword>| value.as_ref().map(|k| compile(mode, k)).transpose();
+                Ok(AllowRule {
+                    process_name: field(entry.mode, &entry.process_name)?,
+                    exe_path: field(entry.mode, &entry.exe_path)?,
+                    extension_id: field(MatchMode::Exact, &entry.extension_id)?,
+                    window_title: field(entry.mode, &entry.window_title)?,
+                    id: entry.id,
+                })
+            }
+        }
+
+        deserializer.deserialize_any(AllowRuleVisitor)
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReportingConfig {
+    pub local_log: Option<String>,
+    pub remote_endpoint: Option<String>,
+}
+
+/// A non-empty, trimmed match keyword. Case is preserved so regex classes
+/// such as `\d` survive; matching itself is case-insensitive.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Keyword(String);
+
+impl Keyword {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl TryFrom<String> for Keyword {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let trimmed = value.trim();
+        if trimmed.is_empty() {
+            return Err("keyword must not be empty".to_string());
+        }
+        Ok(Keyword(trimmed.to_string()))
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    Invalid {
+        line: Option<usize>,
+        column: Option<usize>,
+        message: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io { path, source } => {
+                write!(f, "failed to read {}: {}", path.display(), source)
+            }
+            ConfigError::Invalid { line: Some(line), column: Some(column), message } => {
+                write!(f, "line {}, column {}: {}", line, column, message)
+            }
+            ConfigError::Invalid { message, .. } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io { source, .. } => Some(source),
+            ConfigError::Invalid { .. } => None,
+        }
+    }
+}
+
+impl From<serde_yaml::Error> for ConfigError {
+    fn from(err: serde_yaml::Error) -> Self {
+        let location = err.location();
+        let mut message = err.to_string();
+        // serde_yaml appends " at line X column Y"; we carry those separately.
+        if let Some(loc) = &location {
+            let suffix = format!(" at line {} column {}", loc.line(), loc.column());
+            if let Some(stripped) = message.strip_suffix(&suffix) {
+                message = stripped.to_string();
+            }
+        }
+        ConfigError::Invalid {
+            line: location.as_ref().map(|l| l.line()),
+            column: location.as_ref().map(|l| l.column()),
+            message,
+        }
+    }
+}
+
+/// One step from a YAML node to a child: a mapping key or a sequence index.
+#[derive(Debug, Clone)]
+pub(crate) enum PathStep {
+    Key(String),
+    Index(usize),
+}
+
+impl From<&str> for PathStep {
+    fn from(key: &str) -> Self {
+        PathStep::Key(key.to_string())
+    }
+}
+
+impl From<usize> for PathStep {
+    fn from(index: usize) -> Self {
+        PathStep::Index(index)
+    }
+}
+
+/// `prefix` followed by `steps`.
+pub(crate) fn path(prefix: &[PathStep], steps: impl IntoIterator<Item = PathStep>) -> Vec<PathStep> {
+    prefix.iter().cloned().chain(steps).collect()
+}
+
+/// The text a config was parsed from, kept so the checks that run after
+/// parsing can report where the node they reject is.
+pub(crate) struct Source<'a>(&'a str);
+
+impl Source<'_> {
+    /// An `Invalid` error at the node `path` leads to. serde_yaml only tells
+    /// where a node is when deserializing it fails, so the document is walked
+    /// again and made to fail there.
+    pub(crate) fn invalid(&self, path: &[PathStep], message: String) -> ConfigError {
+        let location = Locate(path)
+            .deserialize(serde_yaml::Deserializer::from_str(self.0))
+            .err()
+            .and_then(|err| err.location());
+        ConfigError::Invalid {
+            line: location.as_ref().map(|l| l.line()),
+            column: location.as_ref().map(|l| l.column()),
+            message,
+        }
+    }
+
+    /// `err` located at `path` when it has no location of its own.
+    pub(crate) fn locate(&self, err: ConfigError, path: &[PathStep]) -> ConfigError {
+        match err {
+            ConfigError::Invalid { line: None, message, .. } => self.invalid(path, message),
+            other => other,
+        }
+    }
+}
+
+/// Walks down `path` and fails at the node it ends on. Scalars fail through
+/// the visitor's default `invalid_type` error.
+struct Locate<'p>(&'p [PathStep]);
+
+impl<'de> DeserializeSeed<'de> for Locate<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Locate<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a mapping or sequence on the path")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let Some((PathStep::Key(key), rest)) = self.0.split_first() else {
+            return Err(de::Error::custom("located"));
+        };
+        while let Some(candidate) = map.next_key::<String>()? {
+            if &candidate == key {
+                return map.next_value_seed(Locate(rest));
+            }
+            map.next_value::<IgnoredAny>()?;
+        }
+        Ok(())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let Some((PathStep::Index(index), rest)) = self.0.split_first() else {
+            return Err(de::Error::custom("located"));
+        };
+        for _ in 0..*index {
+            if seq.next_element::<IgnoredAny>()?.is_none() {
+                return Ok(());
+            }
+        }
+        seq.next_element_seed(Locate(rest)).map(|_| ())
+    }
+}
+
+impl Config {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, ConfigError> {
+        if text.trim().is_empty() {
+            return Ok(Config::default());
+        }
+        let config: Config = serde_yaml::from_str(text)?;
+        if let Some(policy) = &config.policy {
+            let source = Source(text);
+            let base = vec![PathStep::from("policy")];
+            let profiles = policy.profiles.iter().map(|(name, profile)| {
+                let at = path(&base, ["profiles".into(), name.as_str().into()]);
+                (at, &profile.rules, &profile.required_capabilities)
+            });
+            let sections = std::iter::once((base.clone(), &policy.rules, &policy.required_capabilities)).chain(profiles);
+            for (at, rules, required) in sections {
+                if let Some(rules) = rules {
+                    rules.validate_fields(&source, &path(&at, ["rules".into()]))?;
+                }
+                validate_required(required, &source, &path(&at, ["required_capabilities".into()]))?;
+            }
+            policy.validate_profiles(&source)?;
+        }
+        Ok(config)
+    }
+}
+
+fn rules(specs: &Option<Vec<RuleSpec>>, category: Category, base: Vec<Rule>) -> Vec<Rule> {
+    match specs {
+        Some(specs) => specs.iter().map(|spec| spec.to_rule(category)).collect(),
+        None => base,
+    }
+}
+
+fn add_rules(specs: &Option<Vec<RuleSpec>>, category: Category, rules: &mut Vec<Rule>) {
+    for rule in specs.iter().flatten().map(|spec| spec.to_rule(category)) {
+        match rules.iter_mut().find(|existing| existing.id == rule.id) {
+            Some(existing) => *existing = rule,
+            None => rules.push(rule),
+        }
+    }
+}
+
+impl RulesConfig {
+    /// Overlays the configured categories on top of `base`.
+    pub fn apply(&self, base: RuleSet) -> RuleSet {
+        RuleSet {
+            browsers: rules(&self.browsers, Category::Browser, base.browsers),
+            extensions: rules(&self.extensions, Category::Extension, base.extensions),
+            processes: rules(&self.processes, Category::Process, base.processes),
+            network_adapters: rules(&self.network_adapters, Category::Network, base.network_adapters),
+            hosts: rules(&self.hosts, Category::Hosts, base.hosts),
+            windows: rules(&self.windows, Category::Window, base.windows),
+            max_displays: self.max_displays.unwrap_or(base.max_displays),
+        }
+    }
+
+    /// Checks that every `fields:` entry names a field of its category.
+    /// `at` is the path of this `rules:` mapping.
+    fn validate_fields(&self, source: &Source, at: &[PathStep]) -> Result<(), ConfigError> {
+        let categories = [
+            ("browsers", &self.browsers, Category::Browser),
+            ("extensions", &self.extensions, Category::Extension),
+            ("processes", &self.processes, Category::Process),
+            ("network_adapters", &self.network_adapters, Category::Network),
+            ("hosts", &self.hosts, Category::Hosts),
+            ("windows", &self.windows, Category::Window),
+        ];
+        for (key, specs, category) in categories {
+            for (index, spec) in specs.iter().flatten().enumerate() {
+                let RuleSpec::Rule { id, fields, .. } = spec else {
+                    continue;
+                };
+                let known = fields_for(category);
+                if let Some(position) = fields.iter().position(|field| !known.contains(&field.as_str())) {
+                    return Err(source.invalid(
+                        &path(at, [key.into(), index.into(), "fields".into(), position.into()]),
+                        format!(
+                            "rule `{}`: {} rules cannot match field `{}` (expected one of: {})",
+                            id,
+                            category,
+                            fields[position],
+                            known.join(", ")
+                        ),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the configured rules to `rules`, replacing any with the same id.
+    /// Used by profiles, which extend their parent instead of replacing it.
+    pub fn extend(&self, rules: &mut RuleSet) {
+        add_rules(&self.browsers, Category::Browser, &mut rules.browsers);
+        add_rules(&self.extensions, Category::Extension, &mut rules.extensions);
+        add_rules(&self.processes, Category::Process, &mut rules.processes);
+        add_rules(&self.network_adapters, Category::Network, &mut rules.network_adapters);
+        add_rules(&self.hosts, Category::Hosts, &mut rules.hosts);
+        add_rules(&self.windows, Category::Window, &mut rules.windows);
+        if let Some(max) = self.max_displays {
+            rules.max_displays = max;
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use crate::verdict::Verdict;
+use crate::violation::{Category, Evidence};
+use platform_common::ProcessInfo;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+/// What a runtime offense leads to, from mildest to harshest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EscalationStep {
+    /// Tell the student and carry on.
+    Warn,
+    /// Carry on, and alert the proctor.
+    NotifyProctor,
+    /// End the session.
+    Terminate,
+}
+
+/// Strike counts at which a category escalates. Below `notify_after` an
+/// offense only warns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Ladder {
+    pub notify_after: u32,
+    pub terminate_after: u32,
+    /// Strikes older than this no longer count. 0 keeps them for the whole
+    /// session.
+    pub decay_seconds: u64,
+}
+
+impl Default for Ladder {
+    fn default() -> Self {
+        Self {
+            notify_after: 2,
+            terminate_after: 3,
+            decay_seconds: 300,
+        }
+    }
+}
+
+impl Ladder {
+    pub fn step(&self, strikes: u32) -> EscalationStep {
+        if strikes >= self.terminate_after {
+            EscalationStep::Terminate
+        } else if strikes >= self.notify_after {
+            EscalationStep::NotifyProctor
+        } else {
+            EscalationStep::Warn
+        }
+    }
+}
+
+/// The `policy.escalation` section: a ladder for every category, with
+/// optional per-category overrides. Fields an override leaves out are taken
+/// from the top-level ladder.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "RawEscalation")]
+pub struct EscalationPolicy {
+    pub ladder: Ladder,
+    pub categories: BTreeMap<Category, Ladder>,
+    /// When the process category reaches `terminate`, stop the offending
+    /// processes instead of ending the session.
+    pub kill_processes: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawEscalation {
+    notify_after: Option<u32>,
+    terminate_after: Option<u32>,
+    decay_seconds: Option<u64>,
+    #[serde(default)]
+    categories: BTreeMap<Category, RawLadder>,
+    #[serde(default)]
+    kill_processes: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawLadder {
+    notify_after: Option<u32>,
+    terminate_after: Option<u32>,
+    decay_seconds: Option<u64>,
+}
+
+impl RawLadder {
+    fn resolve(&self, base: Ladder, scope: &str) -> Result<Ladder, String> {
+        let ladder = Ladder {
+            notify_after: self.notify_after.unwrap_or(base.notify_after),
+            terminate_after: self.terminate_after.unwrap_or(base.terminate_after),
+            decay_seconds: self.decay_seconds.unwrap_or(base.decay_seconds),
+        };
+        if ladder.notify_after == 0 || ladder.notify_after > ladder.terminate_after {
+            return Err(format!(
+                "{}: escalation must satisfy 0 < notify_after <= terminate_after (got {}, {})",
+                scope, ladder.notify_after, ladder.terminate_after
+            ));
+        }
+        Ok(ladder)
+    }
+}
+
+impl TryFrom<RawEscalation> for EscalationPolicy {
+    type Error = String;
+
+    fn try_from(raw: RawEscalation) -> Result<Self, Self::Error> {
+        let top = RawLadder {
+            notify_after: raw.notify_after,
+            terminate_after: raw.terminate_after,
+            decay_seconds: raw.decay_seconds,
+        };
+        let ladder = top.resolve(Ladder::default(), "escalation")?;
+        let categories = raw
+            .categories
+            .iter()
+            .map(|(category, over)| Ok((*category, over.resolve(ladder, category.as_str())?)))
+            .collect::<Result<_, String>>()?;
+        Ok(Self {
+            ladder,
+            categories,
+            kill_processes: raw.kill_processes,
+        })
+    }
+}
+
+impl EscalationPolicy {
+    pub fn ladder_for(&self, category: Category) -> &Ladder {
+        self.categories.get(&category).unwrap_or(&self.ladder)
+    }
+}
+
+/// One category's escalation after a runtime check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Escalation {
+    pub category: Category,
+    /// Strikes within the decay window, including this one.
+    pub strikes: u32,
+    pub step: EscalationStep,
+    /// Whether `step` is harsher than where the category stood after its
+    /// previous strike, or this is its first strike.
+    pub escalated: bool,
+}
+
+/// Counts strikes per category over a session.
+///
+/// Every recorded verdict with at least one blocking violation in a
+/// category is one strike for that category, however many subjects it
+/// matched. Record only what `ViolationLog::record` returns, so that an
+/// offense that stays open counts once and only a new or re-appeared one
+/// strikes again. A single accidental alt-tab only warns and is forgotten
+/// after `decay_seconds`; once every strike of a category has decayed, its
+/// next offense starts the ladder over.
+#[derive(Debug, Clone)]
+pub struct Escalator {
+    policy: EscalationPolicy,
+    strikes: BTreeMap<Category, VecDeque<u64>>,
+    steps: BTreeMap<Category, EscalationStep>,
+}
+
+impl Escalator {
+    pub fn new(policy: EscalationPolicy) -> Self {
+        Self {
+            policy,
+            strikes: BTreeMap::new(),
+            steps: BTreeMap::new(),
+        }
+    }
+
+    /// Adds this check's strikes, seen at `now` (Unix seconds), and returns
+    /// the escalation of every category that was struck.
+    pub fn record(&mut self, verdict: &Verdict, now: u64) -> Vec<Escalation> {
+        let struck: BTreeSet<Category> = verdict.blocking.iter().map(|g| g.violation.category).collect();
+        struck
+            .into_iter()
+            .map(|category| {
+                let ladder = *self.policy.ladder_for(category);
+                let strikes = self.strikes.entry(category).or_default();
+                if ladder.decay_seconds > 0 {
+                    while strikes.front().is_some_and(|&t| now.saturating_sub(t) >= ladder.decay_seconds) {
+                        strikes.pop_front();
+                    }
+                }
+                if strikes.is_empty() {
+                    self.steps.remove(&category);
+                }
+                strikes.push_back(now);
+                let count = strikes.len() as u32;
+                let step = ladder.step(count);
+                let previous = self.steps.insert(category, step);
+                Escalation {
+                    category,
+                    strikes: count,
+                    step,
+                    escalated: previous.is_none_or(|previous| step > previous),
+                }
+            })
+            .collect()
+    }
+
+    /// The processes to stop instead of ending the session: every blocking
+    /// process of `verdict`, once the process category stands at
+    /// `terminate` and the policy sets `kill_processes`. Call after
+    /// `record`; processes that keep coming back are returned every time.
+    pub fn processes_to_kill<'a>(&self, verdict: &'a Verdict) -> Vec<&'a ProcessInfo> {
+        if !self.policy.kill_processes || self.steps.get(&Category::Process) != Some(&EscalationStep::Terminate) {
+            return Vec::new();
+        }
+        let mut processes: Vec<&ProcessInfo> = Vec::new();
+        for group in &verdict.blocking {
+            if let Evidence::Process(process) = &group.violation.evidence {
+                if !processes.iter().any(|p| p.pid == process.pid) {
+                    processes.push(process);
+                }
+            }
+        }
+        processes
+    }
+
+    /// Current strikes of `category` that have not decayed by `now`.
+    pub fn strikes(&self, category: Category, now: u64) -> u32 {
+        let decay = self.policy.ladder_for(category).decay_seconds;
+        self.strikes.get(&category).map_or(0, |strikes| {
+            strikes
+                .iter()
+                .filter(|&&t| decay == 0 || now.saturating_sub(t) < decay)
+                .count() as u32
+        })
+    }
+}
@@ -0,0 +1,138 @@
+use crate::violation::Category;
+use platform_common::{BrowserFamily, BrowserInfo, ExtensionInfo, HostsEntry, NetworkAdapterInfo, ProcessInfo};
+
+/// An inventory item whose fields rules can match by name.
+pub trait Fields {
+    /// Every field a rule may list in `fields`.
+    const FIELDS: &'static [&'static str];
+    /// Fields matched by rules that do not list any.
+    const DEFAULT: &'static [&'static str];
+
+    /// The field's value as text, or `None` when it is unknown.
+    fn field(&self, name: &str) -> Option<String>;
+}
+
+impl Fields for ProcessInfo {
+    const FIELDS: &'static [&'static str] =
+        &["name", "exe_path", "cmdline", "pid", "ppid", "uid", "user", "start_time", "session_id"];
+    const DEFAULT: &'static [&'static str] = &["name"];
+
+    fn field(&self, name: &str) -> Option<String> {
+        match name {
+            "name" => Some(self.name.clone()),
+            "exe_path" => self.exe_path.clone(),
+            "cmdline" => self.cmdline.clone(),
+            "pid" => Some(self.pid.to_string()),
+            "ppid" => self.ppid.map(|v| v.to_string()),
+            "uid" => self.uid.map(|v| v.to_string()),
+            "user" => self.user.clone(),
+            "start_time" => self.start_time.map(|v| v.to_string()),
+            "session_id" => self.session_id.map(|v| v.to_string()),
+            _ => None,
+        }
+    }
+}
+
+impl Fields for ExtensionInfo {
+    const FIELDS: &'static [&'static str] = &[
+        "name",
+        "id",
+        "chromium_id",
+        "gecko_id",
+        "family",
+        "version",
+        "is_enabled",
+        "install_source",
+        "permissions",
+        "host_permissions",
+    ];
+    const DEFAULT: &'static [&'static str] = &["name"];
+
+    fn field(&self, name: &str) -> Option<String> {
+        match name {
+            "name" => Some(self.name.clone()),
+            "id" => Some(self.id.clone()),
+            // The ID of one family only, so a rule cannot hit an extension
+            // of the other engine whose ID happens to contain the keyword.
+            "chromium_id" => (self.family == BrowserFamily::Chromium).then(|| self.id.clone()),
+            "gecko_id" => (self.family == BrowserFamily::Gecko).then(|| self.id.clone()),
+            "family" => Some(self.family.to_string()),
+            "version" => Some(self.version.clone()),
+            "is_enabled" => Some(self.is_enabled.to_string()),
+            "install_source" => Some(self.install_source.to_string()),
+            // Space-separated, so a word keyword matches one entry.
+            "permissions" => Some(self.permissions.join(" ")),
+            "host_permissions" => Some(self.host_permissions.join(" ")),
+            _ => None,
+        }
+    }
+}
+
+impl Fields for BrowserInfo {
+    const FIELDS: &'static [&'static str] = &["name", "install_path", "version"];
+    const DEFAULT: &'static [&'static str] = &["name"];
+
+    fn field(&self, name: &str) -> Option<String> {
+        match name {
+            "name" => Some(self.name.clone()),
+            "install_path" => Some(self.install_path.clone()),
+            "version" => self.version.clone(),
+            _ => None,
+        }
+    }
+}
+
+impl Fields for NetworkAdapterInfo {
+    const FIELDS: &'static [&'static str] = &["name", "description", "mac_address", "kind", "is_up"];
+    const DEFAULT: &'static [&'static str] = &["name", "description"];
+
+    fn field(&self, name: &str) -> Option<String> {
+        match name {
+            "name" => Some(self.name.clone()),
+            "description" => Some(self.description.clone()),
+            "mac_address" => Some(self.mac_address.clone()),
+            "kind" => Some(self.kind.to_string()),
+            "is_up" => Some(self.is_up.to_string()),
+            _ => None,
+        }
+    }
+}
+
+impl Fields for HostsEntry {
+    const FIELDS: &'static [&'static str] = &["domain", "ip"];
+    const DEFAULT: &'static [&'static str] = &["domain"];
+
+    fn field(&self, name: &str) -> Option<String> {
+        match name {
+            "domain" => Some(self.domain.clone()),
+            "ip" => Some(self.ip.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// The title of the focused window.
+pub(crate) struct WindowTitle<'a>(pub &'a str);
+
+impl Fields for WindowTitle<'_> {
+    const FIELDS: &'static [&'static str] = &["title"];
+    const DEFAULT: &'static [&'static str] = &["title"];
+
+    fn field(&self, name: &str) -> Option<String> {
+        (name == "title").then(|| self.0.to_string())
+    }
+}
+
+/// Field names rules of `category` may list. Empty for categories that are
+/// not keyword-matched.
+pub fn fields_for(category: Category) -> &'static [&'static str] {
+    match category {
+        Category::Process => ProcessInfo::FIELDS,
+        Category::Extension => ExtensionInfo::FIELDS,
+        Category::Browser => BrowserInfo::FIELDS,
+        Category::Network => NetworkAdapterInfo::FIELDS,
+        Category::Hosts => HostsEntry::FIELDS,
+        Category::Window => WindowTitle::FIELDS,
+        Category::Display | Category::VM => &[],
+    }
+}
@@ -0,0 +1,181 @@
+use crate::verdict::{Suppression, Verdict};
+use crate::violation::{Evidence, Violation};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Seconds since the Unix epoch.
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+impl Violation {
+    /// Identifies the inventory item the violation is about, so repeated
+    /// detections of the same item can be collapsed.
+    pub fn subject(&self) -> String {
+        match &self.evidence {
+            Evidence::Process(p) => format!("process:{}:{}", p.pid, p.name.to_lowercase()),
+            Evidence::Extension(e) => format!("extension:{}", e.id),
+            Evidence::Browser(b) => format!("browser:{}:{}", b.name.to_lowercase(), b.install_path),
+            Evidence::NetworkAdapter(a) => format!("network:{}", a.name),
+            Evidence::HostsEntry(h) => format!("hosts:{}:{}", h.domain.to_lowercase(), h.ip),
+            Evidence::Displays(_) => "displays".to_string(),
+            Evidence::SuspiciousDisplay(d) => format!("display:{}", d.device_id),
+            Evidence::Window { title } => format!("window:{}", title),
+            Evidence::VirtualMachine => "vm".to_string(),
+            Evidence::ProbeFailure { kind, .. } => format!("probe:{}", kind),
+            Evidence::Capability { probe, .. } => format!("capability:{}", probe),
+        }
+    }
+
+    fn group_key(&self) -> (String, String) {
+        (self.rule_id.clone(), self.subject())
+    }
+}
+
+/// All detections of one rule on one subject.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ViolationGroup {
+    /// The first detection; later ones only update the fields below.
+    #[serde(flatten)]
+    pub violation: Violation,
+    /// Every keyword of the rule that matched the subject.
+    pub keywords: Vec<String>,
+    pub first_seen: u64,
+    pub last_seen: u64,
+    /// Number of checks the subject was detected in.
+    pub occurrences: u32,
+}
+
+impl ViolationGroup {
+    pub fn new(violation: Violation, seen_at: u64) -> Self {
+        Self {
+            keywords: violation.keyword.iter().cloned().collect(),
+            violation,
+            first_seen: seen_at,
+            last_seen: seen_at,
+            occurrences: 1,
+        }
+    }
+
+    pub fn subject(&self) -> String {
+        self.violation.subject()
+    }
+
+    fn add_keywords(&mut self, keywords: impl IntoIterator<Item = String>) {
+        for keyword in keywords {
+            if !self.keywords.contains(&keyword) {
+                self.keywords.push(keyword);
+            }
+        }
+    }
+
+    /// Folds a later detection of the same rule and subject into this one.
+    fn merge(&mut self, later: &ViolationGroup) {
+        self.add_keywords(later.keywords.iter().cloned());
+        self.first_seen = self.first_seen.min(later.first_seen);
+        self.last_seen = self.last_seen.max(later.last_seen);
+        self.occurrences += later.occurrences;
+    }
+}
+
+impl fmt::Display for ViolationGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.violation)?;
+        if self.occurrences > 1 {
+            write!(f, " (seen {} times)", self.occurrences)?;
+        }
+        Ok(())
+    }
+}
+
+/// Collapses the violations of a single check by (rule, subject), keeping
+/// the order in which each group first appeared. A subject matched by
+/// several keywords of one rule becomes one group listing all of them.
+pub fn group_violations(violations: Vec<Violation>, seen_at: u64) -> Vec<ViolationGroup> {
+    let mut groups: Vec<ViolationGroup> = Vec::new();
+    let mut index: BTreeMap<(String, String), usize> = BTreeMap::new();
+    for violation in violations {
+        match index.get(&violation.group_key()) {
+            Some(&i) => groups[i].add_keywords(violation.keyword),
+            None => {
+                index.insert(violation.group_key(), groups.len());
+                groups.push(ViolationGroup::new(violation, seen_at));
+            }
+        }
+    }
+    groups
+}
+
+/// Groups across the checks of a running session.
+///
+/// Each check's verdict is merged into the log. A group is reported again
+/// only when it is new, or when its subject went away for at least one
+/// check and came back, so a process that stays open does not produce a
+/// report every five seconds.
+#[derive(Debug, Clone, Default)]
+pub struct ViolationLog {
+    groups: BTreeMap<(String, String), ViolationGroup>,
+    active: BTreeSet<(String, String)>,
+}
+
+impl ViolationLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Merges `verdict` into the log and returns the part of it worth
+    /// reporting: new or re-appeared groups, with their session-wide counts.
+    /// The returned verdict keeps the profile and risk assessment of `verdict`.
+    pub fn record(&mut self, verdict: &Verdict) -> Verdict {
+        let mut active = BTreeSet::new();
+        let mut fresh = Verdict {
+            profile: verdict.profile.clone(),
+            risk: verdict.risk.clone(),
+            ..Verdict::default()
+        };
+        for group in &verdict.blocking {
+            fresh.blocking.extend(self.merge(group, &mut active));
+        }
+        for group in &verdict.audited {
+            fresh.audited.extend(self.merge(group, &mut active));
+        }
+        for suppression in &verdict.suppressed {
+            if let Some(group) = self.merge(&suppression.group, &mut active) {
+                fresh.suppressed.push(Suppression {
+                    allow_rule_id: suppression.allow_rule_id.clone(),
+                    group,
+                    allowed_by: suppression.allowed_by.clone(),
+                });
+            }
+        }
+        self.active = active;
+        fresh
+    }
+
+    fn merge(&mut self, group: &ViolationGroup, active: &mut BTreeSet<(String, String)>) -> Option<ViolationGroup> {
+        let key = group.violation.group_key();
+        let was_active = self.active.contains(&key);
+        let merged = match self.groups.get_mut(&key) {
+            Some(existing) => {
+                existing.merge(group);
+                existing.clone()
+            }
+            None => {
+                self.groups.insert(key.clone(), group.clone());
+                group.clone()
+            }
+        };
+        active.insert(key);
+        (!was_active).then_some(merged)
+    }
+
+    /// Every group recorded this session, ordered by rule and subject.
+    pub fn groups(&self) -> impl Iterator<Item = &ViolationGroup> {
+        self.groups.values()
+    }
+}
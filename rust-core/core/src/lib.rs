@@ -1,41 +1,481 @@
+use platform_common::{
+    BrowserInfo, Capabilities, DisplayInfo, ExtensionInfo, HostsEntry, NetworkAdapterInfo, Probe, ProbeError,
+    ProbeStatus, ProcessInfo, Support, SystemSnapshot,
+};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
 
-use platform_common::{BrowserInfo, DisplayInfo, ExtensionInfo, HostsEntry, NetworkAdapterInfo, ProcessInfo};
+use fields::WindowTitle;
 
-pub struct PolicyEngine;
+mod config;
+mod escalation;
+mod fields;
+mod group;
+mod matcher;
+mod probe;
+mod profile;
+mod risk;
+mod rules;
+mod verdict;
+mod violation;
+
+pub use config::{
+    Config, ConfigError, Keyword, PolicyConfig, PolicyMode, ReportingConfig, RiskConfig, RuleSpec, RulesConfig,
+};
+pub use escalation::{Escalation, EscalationPolicy, EscalationStep, Escalator, Ladder};
+pub use fields::{fields_for, Fields};
+pub use group::{group_violations, unix_now, ViolationGroup, ViolationLog};
+pub use matcher::{MatchMode, Matcher};
+pub use probe::{capability_rule_id, probe_category, probe_rule_id, ProbeAction, ProbePolicy};
+pub use profile::{ProfileConfig, BASE_PROFILE};
+pub use risk::{RiskAssessment, RiskContribution, RiskOutcome, RiskThresholds, SessionRisk};
+pub use rules::{
+    AllowRule, Rule, RuleSet, BLACKLIST_RULE, MULTIPLE_DISPLAYS_RULE, SUSPICIOUS_DISPLAY_RULE, VIRTUAL_MACHINE_RULE,
+};
+pub use verdict::{Suppression, Verdict};
+pub use violation::{Category, Evidence, MatchTrace, Severity, Violation};
+
+pub struct PolicyEngine {
+    mode: PolicyMode,
+    category_modes: BTreeMap<Category, PolicyMode>,
+    probes: BTreeMap<Category, ProbePolicy>,
+    required_capabilities: BTreeMap<Probe, Support>,
+    whitelist: Vec<AllowRule>,
+    blacklist: Option<Rule>,
+    rules: RuleSet,
+    thresholds: RiskThresholds,
+    /// Weight overrides by rule id.
+    weights: BTreeMap<String, u32>,
+    escalation: EscalationPolicy,
+    profile: String,
+    /// `display.multiple`, `display.suspicious` and `vm.detected` when a
+    /// profile removed them.
+    disabled: BTreeSet<String>,
+    explain: bool,
+}
+
+impl Default for PolicyEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl PolicyEngine {
     pub fn new() -> Self {
-        Self
+        Self {
+            mode: PolicyMode::default(),
+            category_modes: BTreeMap::new(),
+            probes: BTreeMap::new(),
+            required_capabilities: BTreeMap::new(),
+            whitelist: Vec::new(),
+            blacklist: None,
+            rules: RuleSet::default(),
+            thresholds: RiskThresholds::default(),
+            weights: BTreeMap::new(),
+            escalation: EscalationPolicy::default(),
+            profile: BASE_PROFILE.to_string(),
+            disabled: BTreeSet::new(),
+            explain: false,
+        }
+    }
+
+    /// Loads the `policy:` section of a `config.yaml`. Categories the file
+    /// does not mention keep their built-in keyword lists.
+    pub fn from_config(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        Ok(Self::from_policy(&Config::load(path)?.policy.unwrap_or_default()))
+    }
+
+    pub fn from_policy(policy: &PolicyConfig) -> Self {
+        let rules = match &policy.rules {
+            Some(rules) => rules.apply(RuleSet::default()),
+            None => RuleSet::default(),
+        };
+        let mut engine = Self {
+            mode: policy.mode,
+            category_modes: policy.category_modes.clone(),
+            probes: policy.probes.clone(),
+            required_capabilities: policy.required_capabilities.clone(),
+            whitelist: policy.whitelist.clone(),
+            blacklist: None,
+            rules,
+            thresholds: policy.risk.thresholds,
+            weights: policy.risk.weights.clone(),
+            escalation: policy.escalation.clone(),
+            profile: BASE_PROFILE.to_string(),
+            disabled: BTreeSet::new(),
+            explain: false,
+        };
+        engine.add_blacklist(&policy.blacklist);
+        engine.apply_weights();
+        engine
+    }
+
+    /// The base policy with profile `name` applied on top, after the
+    /// profiles it extends. `base` gives the base policy unchanged.
+    pub fn from_profile(policy: &PolicyConfig, name: &str) -> Result<Self, ConfigError> {
+        let mut engine = Self::from_policy(policy);
+        for (profile_name, profile) in policy.profile_chain(name)? {
+            engine
+                .apply_profile(profile)
+                .map_err(|index| profile::invalid(profile::unknown_removal(profile_name, &profile.remove[index])))?;
+        }
+        engine.apply_weights();
+        engine.profile = name.to_string();
+        Ok(engine)
+    }
+
+    /// Fails with the index of a `remove` entry that matches nothing the
+    /// profile inherits.
+    fn apply_profile(&mut self, profile: &ProfileConfig) -> Result<(), usize> {
+        if let Some(mode) = profile.mode {
+            self.mode = mode;
+        }
+        self.category_modes
+            .extend(profile.category_modes.iter().map(|(category, mode)| (*category, *mode)));
+        self.probes.extend(profile.probes.iter().map(|(category, probe)| (*category, *probe)));
+        self.required_capabilities
+            .extend(profile.required_capabilities.iter().map(|(probe, support)| (*probe, *support)));
+        // Removals first, so a profile can drop an inherited rule and add
+        // its own under the same id.
+        for (index, id) in profile.remove.iter().enumerate() {
+            let removed = match id.as_str() {
+                BLACKLIST_RULE => self.blacklist.take().is_some(),
+                MULTIPLE_DISPLAYS_RULE | SUSPICIOUS_DISPLAY_RULE | VIRTUAL_MACHINE_RULE => self.disabled.insert(id.clone()),
+                _ => {
+                    let whitelisted = self.whitelist.len();
+                    self.whitelist.retain(|entry| &entry.id != id);
+                    self.rules.remove(id) || self.whitelist.len() != whitelisted
+                }
+            };
+            if !removed {
+                return Err(index);
+            }
+        }
+        self.whitelist.extend(profile.whitelist.iter().cloned());
+        self.add_blacklist(&profile.blacklist);
+        if let Some(rules) = &profile.rules {
+            rules.extend(&mut self.rules);
+        }
+        Ok(())
+    }
+
+    fn add_blacklist(&mut self, keywords: &[Keyword]) {
+        if keywords.is_empty() {
+            return;
+        }
+        let rule = self.blacklist.get_or_insert_with(|| Rule {
+            id: BLACKLIST_RULE.to_string(),
+            keywords: Vec::new(),
+            severity: Severity::High,
+            weight: Severity::High.default_weight(),
+            fields: Vec::new(),
+            when: Vec::new(),
+        });
+        rule.keywords.extend(
+            keywords
+                .iter()
+                .map(|k| Matcher::new(MatchMode::Word, k.as_str()).expect("escaped keywords compile")),
+        );
+    }
+
+    fn apply_weights(&mut self) {
+        for rule in self.rules.all_mut().chain(self.blacklist.iter_mut()) {
+            if let Some(&weight) = self.weights.get(&rule.id) {
+                rule.weight = weight;
+            }
+        }
+    }
+
+    /// Name of the profile this engine was built from.
+    pub fn profile(&self) -> &str {
+        &self.profile
+    }
+
+    pub fn mode(&self) -> PolicyMode {
+        self.mode
+    }
+
+    /// The effective mode for `category`: its override, or the global mode.
+    pub fn mode_for(&self, category: Category) -> PolicyMode {
+        self.category_modes.get(&category).copied().unwrap_or(self.mode)
+    }
+
+    /// How a failed `category` probe is treated; fail open unless configured.
+    pub fn probe_policy(&self, category: Category) -> ProbePolicy {
+        self.probes.get(&category).copied().unwrap_or_default()
+    }
+
+    /// The least support each probe needs before an exam may start.
+    pub fn required_capabilities(&self) -> &BTreeMap<Probe, Support> {
+        &self.required_capabilities
+    }
+
+    /// The first whitelist entry that covers the subject of `violation`.
+    pub fn allowed_by(&self, violation: &Violation) -> Option<&AllowRule> {
+        self.whitelist.iter().find(|rule| rule.allows(&violation.evidence))
+    }
+
+    /// Explain mode records, for every violation, the matcher, the field it
+    /// inspected, the raw value and the matched span, and for suppressed
+    /// ones the whitelist field that matched. Off by default to keep
+    /// reports small.
+    pub fn set_explain(&mut self, explain: bool) {
+        self.explain = explain;
+    }
+
+    pub fn explains(&self) -> bool {
+        self.explain
+    }
+
+    pub fn thresholds(&self) -> &RiskThresholds {
+        &self.thresholds
+    }
+
+    /// An empty running score using this policy's thresholds.
+    pub fn session_risk(&self) -> SessionRisk {
+        SessionRisk::new(self.thresholds)
+    }
+
+    /// A fresh strike counter for a runtime session.
+    pub fn escalator(&self) -> Escalator {
+        Escalator::new(self.escalation.clone())
+    }
+
+    /// Groups the violations of one check by rule and subject, then sorts
+    /// the groups into blocking and audited ones according to the mode of
+    /// their category. Whitelist entries are checked first: a group they
+    /// cover is recorded as suppressed whatever its mode. Groups in `allow`
+    /// categories are dropped. Blocking and audited groups both count
+    /// towards the risk score, and its thresholds decide the outcome. Only a
+    /// blocking group can take it to `terminate`, and a required check that
+    /// failed closed always does.
+    pub fn decide(&self, violations: Vec<Violation>) -> Verdict {
+        let mut verdict = Verdict {
+            profile: self.profile.clone(),
+            ..Verdict::default()
+        };
+        for group in group_violations(violations, unix_now()) {
+            if let Some(rule) = self.allowed_by(&group.violation) {
+                verdict.suppressed.push(Suppression {
+                    allow_rule_id: rule.id.clone(),
+                    allowed_by: self
+                        .explain
+                        .then(|| rule.allow_match(&group.violation.evidence))
+                        .flatten(),
+                    group,
+                });
+                continue;
+            }
+            match self.mode_for(group.violation.category) {
+                PolicyMode::Block => verdict.blocking.push(group),
+                PolicyMode::Audit => verdict.audited.push(group),
+                PolicyMode::Allow => {}
+            }
+        }
+        let scored = verdict.blocking.iter().chain(&verdict.audited).map(|g| &g.violation);
+        verdict.risk = RiskAssessment::from_violations(scored, &self.thresholds)
+            .bounded(!verdict.blocking.is_empty(), verdict.fails_closed());
+        verdict
     }
 
-    pub fn check_active_window(&self, active_window: &str) -> Option<String> {
-        let active_window_lower = active_window.to_lowercase();
-        let forbidden_window_keywords = ["chatgpt", "discord", "search", "google", "stack overflow", "copilot"];
-        for keyword in forbidden_window_keywords {
-             if active_window_lower.contains(keyword) {
-                 return Some(format!("Forbidden Active Window detected: {}", active_window));
-             }
+    pub fn whitelist(&self) -> &[AllowRule] {
+        &self.whitelist
+    }
+
+    pub fn rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    fn builtin_weight(&self, rule_id: &str, category: Category) -> u32 {
+        self.weights
+            .get(rule_id)
+            .copied()
+            .unwrap_or_else(|| category.default_severity().default_weight())
+    }
+
+    /// Category rules followed by the global blacklist.
+    fn rules_for<'a>(&'a self, category: &'a [Rule]) -> impl Iterator<Item = &'a Rule> {
+        category.iter().chain(self.blacklist.iter())
+    }
+
+    /// A keyword violation of `rule`, traced in explain mode.
+    fn violation(
+        &self,
+        rule: &Rule,
+        category: Category,
+        keyword: &Matcher,
+        field: &str,
+        value: &str,
+        evidence: Evidence,
+    ) -> Violation {
+        Violation {
+            category,
+            severity: rule.severity,
+            rule_id: rule.id.clone(),
+            weight: rule.weight,
+            keyword: Some(keyword.pattern().to_string()),
+            evidence,
+            trace: self.explain.then(|| MatchTrace::new(keyword, field, value)),
         }
-        None
     }
 
-    pub fn check_processes(&self, processes: &[ProcessInfo]) -> Vec<String> {
+    /// A violation of a threshold check that has no `Rule`.
+    fn threshold_violation(
+        &self,
+        rule_id: &str,
+        category: Category,
+        limit: String,
+        field: &str,
+        value: String,
+        evidence: Evidence,
+    ) -> Violation {
+        Violation {
+            category,
+            severity: category.default_severity(),
+            rule_id: rule_id.to_string(),
+            weight: self.builtin_weight(rule_id, category),
+            keyword: None,
+            evidence,
+            trace: self.explain.then(|| MatchTrace {
+                matcher: limit,
+                field: field.to_string(),
+                value,
+                span: None,
+            }),
+        }
+    }
+
+    /// Matches `rules` against `item`. Each keyword is tried on the rule's
+    /// fields in order, and the first field it matches gives one violation.
+    fn check_item<'a, T: Fields>(
+        &self,
+        rules: impl Iterator<Item = &'a Rule>,
+        category: Category,
+        item: &T,
+        evidence: impl Fn() -> Evidence,
+    ) -> Vec<Violation> {
         let mut violations = Vec::new();
-        let forbidden_process_keywords = [
-            "wireshark", "fiddler", "charles", "cheatengine", "obs", "xsplit", 
-            "teamviewer", "anydesk", "discord", "skype", "slack", "telegram"
-        ];
-        
-        for process in processes {
-            let name_lower = process.name.to_lowercase();
-            for keyword in forbidden_process_keywords {
-                if name_lower.contains(keyword) {
-                    violations.push(format!("Forbidden Process detected: {} (PID: {})", process.name, process.pid));
+        for rule in rules {
+            if !rule.when.iter().all(|(field, value)| item.field(field).as_deref() == Some(value.as_str())) {
+                continue;
+            }
+            let values: Vec<(&str, String)> = if rule.fields.is_empty() {
+                T::DEFAULT.iter().filter_map(|f| Some((*f, item.field(f)?))).collect()
+            } else {
+                rule.fields
+                    .iter()
+                    .filter_map(|f| Some((f.as_str(), item.field(f)?)))
+                    .collect()
+            };
+            for keyword in &rule.keywords {
+                if let Some((field, value)) = values.iter().find(|(_, value)| keyword.is_match(value)) {
+                    violations.push(self.violation(rule, category, keyword, field, value, evidence()));
                 }
             }
         }
         violations
     }
+
+    pub fn check_active_window(&self, active_window: &str) -> Option<Violation> {
+        self.check_item(
+            self.rules_for(&self.rules.windows),
+            Category::Window,
+            &WindowTitle(active_window),
+            || Evidence::Window { title: active_window.to_string() },
+        )
+        .into_iter()
+        .next()
+    }
+
+    /// A `probe.<category>` violation when the `category` probe failed with
+    /// `error` and the policy fails closed for it; `None` when it fails open.
+    pub fn check_probe(&self, category: Category, error: &ProbeError) -> Option<Violation> {
+        self.check_probe_status(category, &ProbeStatus::from(error))
+    }
+
+    /// `check_probe` for a recorded status, e.g. from a saved snapshot.
+    pub fn check_probe_status(&self, category: Category, status: &ProbeStatus) -> Option<Violation> {
+        let message = status.message()?;
+        let (setting, action) = self.probe_policy(category).action(status);
+        if action == ProbeAction::FailOpen {
+            return None;
+        }
+        Some(self.threshold_violation(
+            &probe_rule_id(category),
+            category,
+            format!("{}:{}", setting, action.as_str()),
+            "probe",
+            message.to_string(),
+            Evidence::ProbeFailure {
+                kind: status.kind().to_string(),
+                message: message.to_string(),
+            },
+        ))
+    }
+
+    /// A `capability.<probe>` violation for every required probe that
+    /// `capabilities` supports less than required.
+    pub fn check_capabilities(&self, capabilities: &Capabilities) -> Vec<Violation> {
+        self.required_capabilities
+            .iter()
+            .filter_map(|(probe, required)| {
+                let category = probe_category(*probe)?;
+                let support = capabilities.support(*probe);
+                (support < *required).then(|| {
+                    self.threshold_violation(
+                        &capability_rule_id(*probe),
+                        category,
+                        format!("requires:{}", required),
+                        "capability",
+                        support.to_string(),
+                        Evidence::Capability {
+                            probe: *probe,
+                            support,
+                            required: *required,
+                        },
+                    )
+                })
+            })
+            .collect()
+    }
+
+    pub fn check_processes(&self, processes: &[ProcessInfo]) -> Vec<Violation> {
+        processes
+            .iter()
+            .flat_map(|process| {
+                self.check_item(self.rules_for(&self.rules.processes), Category::Process, process, || {
+                    Evidence::Process(process.clone())
+                })
+            })
+            .collect()
+    }
+
+    /// Checks everything in `snapshot`, including the probes that failed
+    /// and the capabilities the policy requires, and decides the
+    /// violations. Snapshots from agents that declared no capabilities are
+    /// not held to the requirements.
+    pub fn evaluate_snapshot(&self, snapshot: &SystemSnapshot) -> Verdict {
+        let mut violations = self.check_inventory(
+            &snapshot.browsers,
+            &snapshot.extensions,
+            &snapshot.displays,
+            &snapshot.processes,
+            &snapshot.network_adapters,
+            &snapshot.hosts,
+            snapshot.active_window.as_deref().unwrap_or_default(),
+            snapshot.is_vm(),
+        );
+        violations.extend(snapshot.failures().filter_map(|(probe, status)| {
+            self.check_probe_status(probe_category(probe)?, status)
+        }));
+        if !snapshot.capabilities.is_empty() {
+            violations.extend(self.check_capabilities(&snapshot.capabilities));
+        }
+        self.decide(violations)
+    }
+
+    /// Checks a full inventory and decides the violations.
+    #[allow(clippy::too_many_arguments)]
     pub fn evaluate(
         &self,
         browsers: &[BrowserInfo],
@@ -46,90 +486,112 @@ impl PolicyEngine {
         hosts: &[HostsEntry],
         active_window: &str,
         is_vm: bool,
-    ) -> Vec<String> {
-        println!("Evaluating policy...");
-        
-        // AI-enhanced detection list
-        let suspicious_browser_keywords = ["tor", "comet", "ulaa"];
-        let suspicious_extension_keywords = [
-            "postman", "vulners", "shodan", "wappalyzer", 
-            "gpt", "copilot", "perplexity", "ai assistant", 
-            "hack", "proxy", "vpn", "requestly"
-        ];
-        let forbidden_process_keywords = [
-            "wireshark", "fiddler", "charles", "cheatengine", "obs", "xsplit", 
-            "teamviewer", "anydesk", "discord", "skype", "slack", "telegram"
-        ];
+    ) -> Verdict {
+        self.decide(self.check_inventory(
+            browsers,
+            extensions,
+            displays,
+            processes,
+            adapters,
+            hosts,
+            active_window,
+            is_vm,
+        ))
+    }
 
+    /// The violations of a full inventory, undecided, so the caller can add
+    /// those of failed probes before calling `decide`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn check_inventory(
+        &self,
+        browsers: &[BrowserInfo],
+        extensions: &[ExtensionInfo],
+        displays: &[DisplayInfo],
+        processes: &[ProcessInfo],
+        adapters: &[NetworkAdapterInfo],
+        hosts: &[HostsEntry],
+        active_window: &str,
+        is_vm: bool,
+    ) -> Vec<Violation> {
         let mut violations = Vec::new();
 
-        if is_vm {
-            violations.push("Virtual Machine Detected".to_string());
+        if is_vm && !self.disabled.contains(VIRTUAL_MACHINE_RULE) {
+            violations.push(self.threshold_violation(
+                VIRTUAL_MACHINE_RULE,
+                Category::VM,
+                "check_vm".to_string(),
+                "is_vm",
+                "true".to_string(),
+                Evidence::VirtualMachine,
+            ));
         }
 
-        for process in processes {
-            let name_lower = process.name.to_lowercase();
-            for keyword in forbidden_process_keywords {
-                if name_lower.contains(keyword) {
-                    violations.push(format!("Forbidden Process detected: {} (PID: {})", process.name, process.pid));
-                }
-            }
-        }
+        violations.extend(self.check_processes(processes));
 
         // Network Adapter Checks (VPN/Proxy)
-        let vpn_keywords = ["tap", "tun", "vpn", "wireguard", "hamachi", "openvpn", "zerotier"];
         for adapter in adapters {
-            let name_lower = adapter.name.to_lowercase();
-            let desc_lower = adapter.description.to_lowercase();
-            for keyword in vpn_keywords {
-                if name_lower.contains(keyword) || desc_lower.contains(keyword) {
-                    violations.push(format!("Suspicious Network Adapter detected: {} ({})", adapter.name, adapter.description));
-                }
-            }
+            violations.extend(self.check_item(
+                self.rules_for(&self.rules.network_adapters),
+                Category::Network,
+                adapter,
+                || Evidence::NetworkAdapter(adapter.clone()),
+            ));
         }
 
         // Hosts File Checks
-        let suspicious_domains = ["cheat", "brainly", "quizlet", "coursehero", "chegg", "openai"];
         for entry in hosts {
-            let domain_lower = entry.domain.to_lowercase();
-            for keyword in suspicious_domains {
-                if domain_lower.contains(keyword) {
-                    violations.push(format!("Suspicious Hosts Entry: {} -> {}", entry.ip, entry.domain));
-                }
-            }
+            violations.extend(self.check_item(self.rules_for(&self.rules.hosts), Category::Hosts, entry, || {
+                Evidence::HostsEntry(entry.clone())
+            }));
         }
 
         // Active Window Check
         // If the window title suggests they are not in the assessment (which has a specific title potentially)
         // or if it matches a forbidden tool
-        let active_window_lower = active_window.to_lowercase();
-        let forbidden_window_keywords = ["chatgpt", "discord", "search", "google", "stack overflow", "copilot"];
-        for keyword in forbidden_window_keywords {
-             if active_window_lower.contains(keyword) {
-                 violations.push(format!("Forbidden Active Window detected: {}", active_window));
-             }
-        }
+        violations.extend(self.check_active_window(active_window));
 
         for browser in browsers {
-            let name_lower = browser.name.to_lowercase();
-            for keyword in suspicious_browser_keywords {
-                if name_lower.contains(keyword) {
-                    violations.push(format!("Suspicious Browser detected: {}", browser.name));
-                }
-            }
+            violations.extend(self.check_item(
+                self.rules_for(&self.rules.browsers),
+                Category::Browser,
+                browser,
+                || Evidence::Browser(browser.clone()),
+            ));
         }
 
         for ext in extensions {
-            let name_lower = ext.name.to_lowercase();
-            for keyword in suspicious_extension_keywords {
-                if name_lower.contains(keyword) {
-                    violations.push(format!("Suspicious Extension detected: {} ({})", ext.name, ext.id));
-                }
-            }
+            violations.extend(self.check_item(
+                self.rules_for(&self.rules.extensions),
+                Category::Extension,
+                ext,
+                || Evidence::Extension(ext.clone()),
+            ));
         }
 
-        if displays.len() > 1 {
-            violations.push(format!("Multiple Displays detected: {} monitors found", displays.len()));
+        if displays.len() > self.rules.max_displays && !self.disabled.contains(MULTIPLE_DISPLAYS_RULE) {
+            violations.push(self.threshold_violation(
+                MULTIPLE_DISPLAYS_RULE,
+                Category::Display,
+                format!("max_displays:{}", self.rules.max_displays),
+                "displays",
+                displays.len().to_string(),
+                Evidence::Displays(displays.to_vec()),
+            ));
+        }
+
+        // Dummy plugs and capture devices, whatever the display count.
+        if !self.disabled.contains(SUSPICIOUS_DISPLAY_RULE) {
+            for display in displays {
+                let Some(flag) = display.flag else { continue };
+                violations.push(self.threshold_violation(
+                    SUSPICIOUS_DISPLAY_RULE,
+                    Category::Display,
+                    "display_flag".to_string(),
+                    "flag",
+                    format!("{}:{}", display.name, flag),
+                    Evidence::SuspiciousDisplay(display.clone()),
+                ));
+            }
         }
 
         violations
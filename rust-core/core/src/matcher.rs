@@ -0,0 +1,148 @@
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::Range;
+
+/// How a rule keyword is compared against an inventory field. All modes are
+/// case-insensitive.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchMode {
+    /// The whole value equals the keyword.
+    Exact,
+    /// The keyword appears with no letter directly before or after it, so
+    /// "obs" matches "obs64.exe" and "OBS Studio" but not "jobs".
+    #[default]
+    Word,
+    /// The value starts with the keyword.
+    Prefix,
+    /// Shell-style pattern over the whole value: `*`, `?` and `[...]`.
+    Glob,
+    /// Unanchored regular expression.
+    Regex,
+    /// The keyword appears anywhere in the value. Prone to false positives;
+    /// kept for rules that really want it.
+    Substring,
+}
+
+impl fmt::Display for MatchMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MatchMode::Exact => "exact",
+            MatchMode::Word => "word",
+            MatchMode::Prefix => "prefix",
+            MatchMode::Glob => "glob",
+            MatchMode::Regex => "regex",
+            MatchMode::Substring => "substring",
+        })
+    }
+}
+
+/// A compiled keyword.
+#[derive(Debug, Clone)]
+pub struct Matcher {
+    mode: MatchMode,
+    pattern: String,
+    regex: Regex,
+}
+
+impl PartialEq for Matcher {
+    fn eq(&self, other: &Self) -> bool {
+        self.mode == other.mode && self.pattern == other.pattern
+    }
+}
+
+impl Matcher {
+    pub fn new(mode: MatchMode, pattern: &str) -> Result<Self, String> {
+        let source = match mode {
+            MatchMode::Exact => format!("^(?:{})$", regex::escape(pattern)),
+            MatchMode::Word | MatchMode::Substring => regex::escape(pattern),
+            MatchMode::Prefix => format!("^(?:{})", regex::escape(pattern)),
+            MatchMode::Glob => glob_to_regex(pattern)?,
+            MatchMode::Regex => pattern.to_string(),
+        };
+        let regex = RegexBuilder::new(&source)
+            .case_insensitive(true)
+            .build()
+            .map_err(|e| format!("invalid {} pattern `{}`: {}", mode, pattern, e))?;
+        Ok(Self {
+            mode,
+            pattern: pattern.to_string(),
+            regex,
+        })
+    }
+
+    pub fn mode(&self) -> MatchMode {
+        self.mode
+    }
+
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Byte range of the first match in `value`.
+    pub fn find(&self, value: &str) -> Option<Range<usize>> {
+        if self.mode != MatchMode::Word {
+            return self.regex.find(value).map(|m| m.range());
+        }
+        self.regex
+            .find_iter(value)
+            .map(|m| m.range())
+            .find(|range| is_word_bounded(value, range))
+    }
+
+    pub fn is_match(&self, value: &str) -> bool {
+        self.find(value).is_some()
+    }
+}
+
+impl fmt::Display for Matcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.mode, self.pattern)
+    }
+}
+
+/// Letters on either side of a match mean it sits inside a longer word.
+/// Digits and punctuation do not, so version suffixes like "obs64" still
+/// count as the word "obs".
+fn is_word_bounded(value: &str, range: &Range<usize>) -> bool {
+    let before = value[..range.start].chars().next_back();
+    let after = value[range.end..].chars().next();
+    !before.is_some_and(char::is_alphabetic) && !after.is_some_and(char::is_alphabetic)
+}
+
+fn glob_to_regex(glob: &str) -> Result<String, String> {
+    let mut out = String::from("^");
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => out.push_str(".*"),
+            '?' => out.push('.'),
+            '[' => {
+                out.push('[');
+                if chars.peek() == Some(&'!') {
+                    chars.next();
+                    out.push('^');
+                }
+                let mut closed = false;
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        closed = true;
+                        break;
+                    }
+                    if c == '\\' || c == '[' || c == '^' {
+                        out.push('\\');
+                    }
+                    out.push(c);
+                }
+                if !closed {
+                    return Err(format!("unclosed `[` in glob `{}`", glob));
+                }
+                out.push(']');
+            }
+            c => out.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    out.push('$');
+    Ok(out)
+}
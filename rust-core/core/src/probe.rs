@@ -0,0 +1,93 @@
+use crate::config::{path, ConfigError, PathStep, Source};
+use crate::violation::Category;
+use platform_common::{Probe, ProbeStatus, Support};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// What a probe that produced no result means for its category.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProbeAction {
+    /// Carry on as if the probe had found nothing.
+    #[default]
+    FailOpen,
+    /// Record a `probe.<category>` violation, decided like any other
+    /// violation of the category.
+    FailClosed,
+}
+
+impl ProbeAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ProbeAction::FailOpen => "fail_open",
+            ProbeAction::FailClosed => "fail_closed",
+        }
+    }
+}
+
+/// An entry of `policy.probes`, e.g. `vm: { on_error: fail_closed }`.
+/// Probes the platform does not implement are kept apart from probes that
+/// broke, since a missing probe says nothing about the machine.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProbePolicy {
+    #[serde(default)]
+    pub on_error: ProbeAction,
+    #[serde(default)]
+    pub on_unsupported: ProbeAction,
+}
+
+impl ProbePolicy {
+    /// The action for a probe that ended with `status`, and the setting it
+    /// came from.
+    pub fn action(&self, status: &ProbeStatus) -> (&'static str, ProbeAction) {
+        if status.is_unsupported() {
+            ("on_unsupported", self.on_unsupported)
+        } else {
+            ("on_error", self.on_error)
+        }
+    }
+}
+
+/// The category whose policy covers `probe`. The antivirus inventory is
+/// informational and has none.
+pub fn probe_category(probe: Probe) -> Option<Category> {
+    match probe {
+        Probe::Antivirus => None,
+        Probe::Browsers => Some(Category::Browser),
+        Probe::Extensions => Some(Category::Extension),
+        Probe::Displays => Some(Category::Display),
+        Probe::Vm => Some(Category::VM),
+        Probe::Processes => Some(Category::Process),
+        Probe::NetworkAdapters => Some(Category::Network),
+        Probe::Hosts => Some(Category::Hosts),
+        Probe::ActiveWindow => Some(Category::Window),
+    }
+}
+
+/// Id of the violation recorded when the `category` probe fails closed.
+pub fn probe_rule_id(category: Category) -> String {
+    format!("probe.{}", category)
+}
+
+/// Id of the violation recorded when the platform declares less support for
+/// `probe` than the policy requires.
+pub fn capability_rule_id(probe: Probe) -> String {
+    format!("capability.{}", probe)
+}
+
+/// Checks that every required probe has a category to report under. `at`
+/// is the path of the `required_capabilities:` mapping.
+pub(crate) fn validate_required(
+    required: &BTreeMap<Probe, Support>,
+    source: &Source,
+    at: &[PathStep],
+) -> Result<(), ConfigError> {
+    match required.keys().find(|probe| probe_category(**probe).is_none()) {
+        Some(probe) => Err(source.invalid(
+            &path(at, [probe.as_str().into()]),
+            format!("`{}` has no category and cannot be a required capability", probe),
+        )),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,139 @@
+use crate::config::{path, ConfigError, Keyword, PathStep, PolicyConfig, PolicyMode, RulesConfig, Source};
+use crate::probe::ProbePolicy;
+use crate::rules::AllowRule;
+use crate::violation::Category;
+use crate::PolicyEngine;
+use platform_common::{Probe, Support};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Name of the policy described by the top level of `policy:`. Every
+/// profile inherits from it, directly or through `extends`.
+pub const BASE_PROFILE: &str = "base";
+
+/// A named variant of the base policy for one kind of assessment, e.g. an
+/// open-book exam that allows a PDF reader or a coding exam that allows
+/// VS Code.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileConfig {
+    /// Profile to start from. Defaults to the base policy.
+    pub extends: Option<String>,
+    /// Exam ids that select this profile at agent startup.
+    #[serde(default)]
+    pub exams: Vec<String>,
+    /// Replaces the inherited `mode`.
+    pub mode: Option<PolicyMode>,
+    /// Merged over the inherited per-category modes.
+    #[serde(default)]
+    pub category_modes: BTreeMap<Category, PolicyMode>,
+    /// Merged over the inherited probe settings.
+    #[serde(default)]
+    pub probes: BTreeMap<Category, ProbePolicy>,
+    /// Merged over the inherited required capabilities.
+    #[serde(default)]
+    pub required_capabilities: BTreeMap<Probe, Support>,
+    /// Added to the inherited whitelist.
+    #[serde(default)]
+    pub whitelist: Vec<AllowRule>,
+    /// Added to the inherited blacklist.
+    #[serde(default)]
+    pub blacklist: Vec<Keyword>,
+    /// Rules added to the inherited ones. A rule with the id of an inherited
+    /// rule replaces it.
+    pub rules: Option<RulesConfig>,
+    /// Ids of inherited rules and whitelist entries to drop. Also accepts
+    /// `blacklist`, `display.multiple`, `display.suspicious` and `vm.detected`.
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
+
+pub(crate) fn invalid(message: String) -> ConfigError {
+    ConfigError::Invalid {
+        line: None,
+        column: None,
+        message,
+    }
+}
+
+pub(crate) fn unknown_removal(profile: &str, id: &str) -> String {
+    format!("profile `{}` removes `{}`, which is not an inherited rule or whitelist entry", profile, id)
+}
+
+impl PolicyConfig {
+    /// The profile whose `exams` list contains `exam_id`. An exam no profile
+    /// lists is an error rather than a silent fallback to the base policy.
+    pub fn profile_for_exam(&self, exam_id: &str) -> Result<&str, ConfigError> {
+        self.profiles
+            .iter()
+            .find(|(_, profile)| profile.exams.iter().any(|exam| exam == exam_id))
+            .map(|(name, _)| name.as_str())
+            .ok_or_else(|| invalid(format!("no profile lists exam `{}`", exam_id)))
+    }
+
+    /// `name` and the profiles it extends, base-most first. Empty for the
+    /// base policy itself.
+    pub fn profile_chain(&self, name: &str) -> Result<Vec<(&str, &ProfileConfig)>, ConfigError> {
+        let mut chain: Vec<(&str, &ProfileConfig)> = Vec::new();
+        let mut next = Some(name);
+        while let Some(current) = next.filter(|n| *n != BASE_PROFILE) {
+            if let Some(start) = chain.iter().position(|(seen, _)| *seen == current) {
+                let cycle: Vec<&str> = chain[start..].iter().map(|(seen, _)| *seen).chain([current]).collect();
+                return Err(invalid(format!("profiles extend each other in a cycle: {}", cycle.join(" -> "))));
+            }
+            let (key, profile) = self
+                .profiles
+                .get_key_value(current)
+                .ok_or_else(|| invalid(format!("unknown profile `{}`", current)))?;
+            chain.push((key.as_str(), profile));
+            next = profile.extends.as_deref();
+        }
+        chain.reverse();
+        Ok(chain)
+    }
+
+    /// Checks that every profile resolves, including its removals, and that
+    /// no exam id selects more than one profile.
+    pub(crate) fn validate_profiles(&self, source: &Source) -> Result<(), ConfigError> {
+        let at = |name: &str, steps: Vec<PathStep>| path(&["policy".into(), "profiles".into(), name.into()], steps);
+        if self.profiles.contains_key(BASE_PROFILE) {
+            return Err(source.invalid(
+                &at(BASE_PROFILE, vec![]),
+                format!("`{}` is reserved for the top-level policy", BASE_PROFILE),
+            ));
+        }
+        // Unknown parents first, so the error points at the profile that
+        // names one rather than at a profile further down the chain.
+        for (name, profile) in &self.profiles {
+            if let Some(parent) = profile.extends.as_deref() {
+                if parent != BASE_PROFILE && !self.profiles.contains_key(parent) {
+                    return Err(source.invalid(&at(name, vec!["extends".into()]), format!("unknown profile `{}`", parent)));
+                }
+            }
+        }
+        let mut exams = BTreeMap::new();
+        for (name, profile) in &self.profiles {
+            let chain = self
+                .profile_chain(name)
+                .map_err(|err| source.locate(err, &at(name, vec!["extends".into()])))?;
+            let mut engine = PolicyEngine::from_policy(self);
+            for (profile_name, profile) in chain {
+                engine.apply_profile(profile).map_err(|index| {
+                    source.invalid(
+                        &at(profile_name, vec!["remove".into(), index.into()]),
+                        unknown_removal(profile_name, &profile.remove[index]),
+                    )
+                })?;
+            }
+            for (index, exam) in profile.exams.iter().enumerate() {
+                if let Some(other) = exams.insert(exam.as_str(), name.as_str()) {
+                    return Err(source.invalid(
+                        &at(name, vec!["exams".into(), index.into()]),
+                        format!("exam `{}` is listed by both profile `{}` and profile `{}`", exam, other, name),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,184 @@
+use crate::verdict::Verdict;
+use crate::violation::{Category, Violation};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// What the aggregate risk score asks the agent to do.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RiskOutcome {
+    #[default]
+    Pass,
+    /// Let the student continue but show them a warning.
+    Warn,
+    /// Continue, and flag the session for a proctor to review.
+    Review,
+    /// Refuse to launch, or end the running session. Only reached with a
+    /// violation in a `block` category.
+    Terminate,
+}
+
+/// Lowest score at which each outcome applies. Scores below `warn` pass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "RawThresholds")]
+pub struct RiskThresholds {
+    pub warn: u32,
+    pub review: u32,
+    pub terminate: u32,
+}
+
+impl Default for RiskThresholds {
+    fn default() -> Self {
+        Self {
+            warn: 1,
+            review: 25,
+            terminate: 50,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawThresholds {
+    warn: u32,
+    review: u32,
+    terminate: u32,
+}
+
+impl TryFrom<RawThresholds> for RiskThresholds {
+    type Error = String;
+
+    fn try_from(raw: RawThresholds) -> Result<Self, Self::Error> {
+        if raw.warn == 0 || raw.warn > raw.review || raw.review > raw.terminate {
+            return Err(format!(
+                "thresholds must satisfy 0 < warn <= review <= terminate (got {}, {}, {})",
+                raw.warn, raw.review, raw.terminate
+            ));
+        }
+        Ok(Self {
+            warn: raw.warn,
+            review: raw.review,
+            terminate: raw.terminate,
+        })
+    }
+}
+
+impl RiskThresholds {
+    pub fn outcome(&self, score: u32) -> RiskOutcome {
+        if score >= self.terminate {
+            RiskOutcome::Terminate
+        } else if score >= self.review {
+            RiskOutcome::Review
+        } else if score >= self.warn {
+            RiskOutcome::Warn
+        } else {
+            RiskOutcome::Pass
+        }
+    }
+}
+
+/// One rule's share of a score. A rule counts once however many subjects
+/// it matched, so a re-detected process does not inflate the score.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RiskContribution {
+    pub rule_id: String,
+    pub category: Category,
+    pub weight: u32,
+    /// How many subjects of this rule were folded into the contribution.
+    pub occurrences: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RiskAssessment {
+    pub score: u32,
+    pub outcome: RiskOutcome,
+    /// Highest weight first.
+    pub contributions: Vec<RiskContribution>,
+}
+
+fn assess(contributions: impl IntoIterator<Item = RiskContribution>, thresholds: &RiskThresholds) -> RiskAssessment {
+    let mut contributions: Vec<_> = contributions.into_iter().collect();
+    contributions.sort_by(|a, b| b.weight.cmp(&a.weight).then_with(|| a.rule_id.cmp(&b.rule_id)));
+    let score = contributions.iter().map(|c| c.weight).fold(0u32, u32::saturating_add);
+    RiskAssessment {
+        score,
+        outcome: thresholds.outcome(score),
+        contributions,
+    }
+}
+
+fn merge(into: &mut BTreeMap<String, RiskContribution>, contribution: RiskContribution) {
+    into.entry(contribution.rule_id.clone())
+        .and_modify(|existing| {
+            existing.weight = existing.weight.max(contribution.weight);
+            existing.occurrences += contribution.occurrences;
+        })
+        .or_insert(contribution);
+}
+
+impl RiskAssessment {
+    /// Applies the policy modes to the outcome of the score: without a
+    /// violation in a `block` category it stops at `Review`, and a required
+    /// check that failed closed terminates whatever the score.
+    pub fn bounded(mut self, blocking: bool, failed_closed: bool) -> Self {
+        if failed_closed {
+            self.outcome = RiskOutcome::Terminate;
+        } else if !blocking {
+            self.outcome = self.outcome.min(RiskOutcome::Review);
+        }
+        self
+    }
+
+    pub fn from_violations<'a>(
+        violations: impl IntoIterator<Item = &'a Violation>,
+        thresholds: &RiskThresholds,
+    ) -> Self {
+        let mut by_rule = BTreeMap::new();
+        for violation in violations {
+            merge(
+                &mut by_rule,
+                RiskContribution {
+                    rule_id: violation.rule_id.clone(),
+                    category: violation.category,
+                    weight: violation.weight,
+                    occurrences: 1,
+                },
+            );
+        }
+        assess(by_rule.into_values(), thresholds)
+    }
+}
+
+/// Running score for a whole session: every rule that fired at pre-flight or
+/// during any runtime check, each counted once.
+#[derive(Debug, Clone)]
+pub struct SessionRisk {
+    thresholds: RiskThresholds,
+    contributions: BTreeMap<String, RiskContribution>,
+    /// Whether any recorded violation was in a `block` category.
+    blocking: bool,
+    failed_closed: bool,
+}
+
+impl SessionRisk {
+    pub fn new(thresholds: RiskThresholds) -> Self {
+        Self {
+            thresholds,
+            contributions: BTreeMap::new(),
+            blocking: false,
+            failed_closed: false,
+        }
+    }
+
+    pub fn record(&mut self, verdict: &Verdict) {
+        for contribution in &verdict.risk.contributions {
+            merge(&mut self.contributions, contribution.clone());
+        }
+        self.blocking |= !verdict.blocking.is_empty();
+        self.failed_closed |= verdict.fails_closed();
+    }
+
+    pub fn assessment(&self) -> RiskAssessment {
+        assess(self.contributions.values().cloned(), &self.thresholds).bounded(self.blocking, self.failed_closed)
+    }
+}
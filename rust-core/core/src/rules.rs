@@ -0,0 +1,242 @@
+use crate::matcher::{MatchMode, Matcher};
+use crate::violation::{Evidence, MatchTrace, Severity};
+use platform_common::{ExtensionInfo, ProcessInfo};
+
+/// A named group of keywords. Violations report the rule id so the server
+/// does not have to infer which list fired.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub id: String,
+    pub keywords: Vec<Matcher>,
+    pub severity: Severity,
+    /// Contribution to the risk score when the rule fires.
+    pub weight: u32,
+    /// Inventory fields the keywords are matched against. Empty means the
+    /// category's default fields, e.g. the process name.
+    pub fields: Vec<String>,
+    /// Field values the item must have exactly for the rule to apply at all,
+    /// e.g. `("is_up", "true")`.
+    pub when: Vec<(String, String)>,
+}
+
+impl Rule {
+    /// A rule weighted by its severity's default weight.
+    pub fn new(id: impl Into<String>, severity: Severity, mode: MatchMode, keywords: &[&str]) -> Result<Self, String> {
+        Ok(Self {
+            id: id.into(),
+            keywords: keywords
+                .iter()
+                .map(|k| Matcher::new(mode, k))
+                .collect::<Result<_, _>>()?,
+            severity,
+            weight: severity.default_weight(),
+            fields: Vec::new(),
+            when: Vec::new(),
+        })
+    }
+
+    /// Restricts the keywords to `field`.
+    pub fn on_field(mut self, field: &str) -> Self {
+        self.fields.push(field.to_string());
+        self
+    }
+
+    /// Applies the rule only to items whose `field` is exactly `value`.
+    pub fn when(mut self, field: &str, value: &str) -> Self {
+        self.when.push((field.to_string(), value.to_string()));
+        self
+    }
+
+    /// Keywords that match `value`.
+    pub fn matches<'a>(&'a self, value: &'a str) -> impl Iterator<Item = &'a Matcher> + 'a {
+        self.keywords.iter().filter(move |keyword| keyword.is_match(value))
+    }
+}
+
+/// An exception evaluated before the deny rules. A violation whose subject
+/// matches any of the fields set here is suppressed and recorded instead of
+/// being enforced.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AllowRule {
+    pub id: String,
+    pub process_name: Option<Matcher>,
+    pub exe_path: Option<Matcher>,
+    pub extension_id: Option<Matcher>,
+    pub window_title: Option<Matcher>,
+}
+
+fn field_match(matcher: &Option<Matcher>, field: &str, value: &str) -> Option<MatchTrace> {
+    matcher
+        .as_ref()
+        .filter(|m| m.is_match(value))
+        .map(|m| MatchTrace::new(m, field, value))
+}
+
+impl AllowRule {
+    pub fn allows_process(&self, process: &ProcessInfo) -> bool {
+        self.process_match(process).is_some()
+    }
+
+    pub fn allows_extension(&self, extension: &ExtensionInfo) -> bool {
+        field_match(&self.extension_id, "id", &extension.id).is_some()
+    }
+
+    pub fn allows_window(&self, title: &str) -> bool {
+        field_match(&self.window_title, "title", title).is_some()
+    }
+
+    pub fn allows(&self, evidence: &Evidence) -> bool {
+        self.allow_match(evidence).is_some()
+    }
+
+    fn process_match(&self, process: &ProcessInfo) -> Option<MatchTrace> {
+        field_match(&self.process_name, "name", &process.name).or_else(|| {
+            process
+                .exe_path
+                .as_deref()
+                .and_then(|path| field_match(&self.exe_path, "exe_path", path))
+        })
+    }
+
+    /// The field of `evidence` this entry matched, if any.
+    pub fn allow_match(&self, evidence: &Evidence) -> Option<MatchTrace> {
+        match evidence {
+            Evidence::Process(process) => self.process_match(process),
+            Evidence::Extension(extension) => field_match(&self.extension_id, "id", &extension.id),
+            Evidence::Window { title } => field_match(&self.window_title, "title", title),
+            _ => None,
+        }
+    }
+}
+
+/// Rules the engine matches against each inventory category.
+///
+/// `RuleSet::default()` holds the built-in rules that ship with the agent; a
+/// config file only needs to list the categories it wants to change.
+#[derive(Debug, Clone)]
+pub struct RuleSet {
+    pub browsers: Vec<Rule>,
+    pub extensions: Vec<Rule>,
+    pub processes: Vec<Rule>,
+    pub network_adapters: Vec<Rule>,
+    pub hosts: Vec<Rule>,
+    pub windows: Vec<Rule>,
+    pub max_displays: usize,
+}
+
+pub const MULTIPLE_DISPLAYS_RULE: &str = "display.multiple";
+pub const SUSPICIOUS_DISPLAY_RULE: &str = "display.suspicious";
+pub const VIRTUAL_MACHINE_RULE: &str = "vm.detected";
+pub const BLACKLIST_RULE: &str = "blacklist";
+
+fn builtin(id: &str, severity: Severity, mode: MatchMode, keywords: &[&str]) -> Rule {
+    Rule::new(id, severity, mode, keywords).expect("built-in rules compile")
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        use MatchMode::{Exact, Regex, Word};
+        use Severity::*;
+        // AI-enhanced detection list. Word matching rejects a keyword with a
+        // letter on either side, so compound product names ("NordVPN",
+        // "HackBar") are listed in full.
+        Self {
+            browsers: vec![
+                builtin("browser.privacy", Medium, Word, &["tor"]),
+                builtin("browser.ai", High, Word, &["comet", "ulaa"]),
+            ],
+            extensions: vec![
+                builtin(
+                    "extension.security-tools",
+                    Medium,
+                    Word,
+                    &["postman", "vulners", "shodan", "wappalyzer", "hack", "hackbar", "hacktools", "requestly"],
+                ),
+                builtin(
+                    "extension.ai-assistant",
+                    High,
+                    Word,
+                    &["gpt", "chatgpt", "webchatgpt", "copilot", "perplexity", "ai assistant"],
+                ),
+                builtin(
+                    "extension.tunnel",
+                    Medium,
+                    Word,
+                    &["proxy", "foxyproxy", "vpn", "nordvpn", "protonvpn", "expressvpn"],
+                ),
+            ],
+            processes: vec![
+                builtin("process.network-inspection", High, Word, &["wireshark", "fiddler", "charles"]),
+                builtin("process.cheat-tools", Critical, Word, &["cheatengine"]),
+                builtin("process.screen-capture", High, Word, &["obs", "xsplit"]),
+                builtin("process.remote-access", Critical, Word, &["teamviewer", "anydesk"]),
+                builtin("process.communication", High, Word, &["discord", "skype", "slack", "telegram"]),
+            ],
+            network_adapters: vec![
+                builtin(
+                    "network.vpn",
+                    Medium,
+                    Word,
+                    // utun<n> is a macOS tunnel interface.
+                    &[
+                        "tap", "tun", "utun", "vpn", "nordvpn", "protonvpn", "wireguard", "hamachi", "openvpn", "zerotier",
+                    ],
+                ),
+                // Tunnels are often named after their interface (wg0, ppp0),
+                // so the probed kind catches what the name does not.
+                builtin("network.vpn-kind", Medium, Exact, &["tun", "tap", "wireguard"])
+                    .on_field("kind")
+                    .when("is_up", "true"),
+            ],
+            hosts: vec![
+                builtin("hosts.study-sites", High, Word, &["cheat", "brainly", "quizlet", "coursehero", "chegg"]),
+                builtin("hosts.ai-services", High, Word, &["openai"]),
+            ],
+            windows: vec![
+                builtin("window.ai-chat", High, Word, &["chatgpt", "copilot"]),
+                builtin("window.communication", Medium, Word, &["discord"]),
+                // "search" and "google" on their own match most browser tabs.
+                builtin("window.web-search", Medium, Regex, &["(google|bing|duckduckgo) search", "stack overflow"]),
+            ],
+            max_displays: 1,
+        }
+    }
+}
+
+impl RuleSet {
+    pub fn all(&self) -> impl Iterator<Item = &Rule> {
+        self.browsers
+            .iter()
+            .chain(self.extensions.iter())
+            .chain(self.processes.iter())
+            .chain(self.network_adapters.iter())
+            .chain(self.hosts.iter())
+            .chain(self.windows.iter())
+    }
+
+    pub fn all_mut(&mut self) -> impl Iterator<Item = &mut Rule> {
+        self.browsers
+            .iter_mut()
+            .chain(self.extensions.iter_mut())
+            .chain(self.processes.iter_mut())
+            .chain(self.network_adapters.iter_mut())
+            .chain(self.hosts.iter_mut())
+            .chain(self.windows.iter_mut())
+    }
+
+    /// Drops every rule with the given id. Returns whether any was found.
+    pub fn remove(&mut self, id: &str) -> bool {
+        let before = self.all().count();
+        for rules in [
+            &mut self.browsers,
+            &mut self.extensions,
+            &mut self.processes,
+            &mut self.network_adapters,
+            &mut self.hosts,
+            &mut self.windows,
+        ] {
+            rules.retain(|rule| rule.id != id);
+        }
+        self.all().count() != before
+    }
+}
@@ -0,0 +1,58 @@
+use crate::group::ViolationGroup;
+use crate::risk::RiskAssessment;
+use crate::risk::RiskOutcome;
+use crate::violation::{Evidence, MatchTrace};
+use serde::{Deserialize, Serialize};
+
+/// Violations sorted by what the policy mode says to do with them, grouped by
+/// rule and subject.
+///
+/// Violations in `allow` categories are dropped entirely; the inventory that
+/// produced them is still profiled and logged by the agent.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Verdict {
+    /// Policy profile the violations were judged against.
+    pub profile: String,
+    /// Violations in `block` categories. They refuse the launch once their
+    /// score, together with the audited ones, reaches `terminate`.
+    pub blocking: Vec<ViolationGroup>,
+    /// Violations in `audit` categories. Recorded, reported and scored,
+    /// never enforced.
+    pub audited: Vec<ViolationGroup>,
+    /// Violations a whitelist entry overrode, kept for the audit trail.
+    pub suppressed: Vec<Suppression>,
+    /// Score of the blocking and audited violations and the outcome it maps
+    /// to. Without blocking violations the outcome is at most `review`.
+    /// Decides whether the verdict blocks.
+    pub risk: RiskAssessment,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Suppression {
+    /// Id of the whitelist entry that matched.
+    pub allow_rule_id: String,
+    pub group: ViolationGroup,
+    /// The field the whitelist entry matched. Filled in in explain mode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_by: Option<MatchTrace>,
+}
+
+impl Verdict {
+    /// Whether the launch is refused or the session ended: the outcome is
+    /// `terminate`.
+    pub fn is_blocked(&self) -> bool {
+        self.risk.outcome == RiskOutcome::Terminate
+    }
+
+    /// Whether a required check that failed or is unavailable blocks. Such a
+    /// check terminates whatever the score.
+    pub fn fails_closed(&self) -> bool {
+        self.blocking.iter().any(|group| {
+            matches!(group.violation.evidence, Evidence::ProbeFailure { .. } | Evidence::Capability { .. })
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocking.is_empty() && self.audited.is_empty() && self.suppressed.is_empty()
+    }
+}
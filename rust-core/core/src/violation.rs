@@ -0,0 +1,183 @@
+use platform_common::{
+    BrowserInfo, DisplayInfo, ExtensionInfo, HostsEntry, NetworkAdapterInfo, Probe, ProcessInfo, Support,
+};
+use crate::matcher::Matcher;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Category {
+    Process,
+    Extension,
+    Browser,
+    Network,
+    Hosts,
+    Display,
+    Window,
+    #[serde(rename = "vm")]
+    VM,
+}
+
+impl Category {
+    pub const ALL: [Category; 8] = [
+        Category::Process,
+        Category::Extension,
+        Category::Browser,
+        Category::Network,
+        Category::Hosts,
+        Category::Display,
+        Category::Window,
+        Category::VM,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Category::Process => "process",
+            Category::Extension => "extension",
+            Category::Browser => "browser",
+            Category::Network => "network",
+            Category::Hosts => "hosts",
+            Category::Display => "display",
+            Category::Window => "window",
+            Category::VM => "vm",
+        }
+    }
+
+    /// Severity given to config rules that do not set one.
+    pub fn default_severity(self) -> Severity {
+        match self {
+            Category::Process | Category::Extension | Category::Browser | Category::VM => Severity::High,
+            Category::Network | Category::Hosts | Category::Display | Category::Window => Severity::Medium,
+        }
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    /// Risk weight of rules that do not set one explicitly.
+    pub fn default_weight(self) -> u32 {
+        match self {
+            Severity::Low => 10,
+            Severity::Medium => 25,
+            Severity::High => 50,
+            Severity::Critical => 100,
+        }
+    }
+}
+
+/// Why a rule or whitelist entry matched: the inventory field it inspected,
+/// the raw value and the part of it that matched.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchTrace {
+    /// The matcher as `mode:pattern`, or the limit for threshold checks
+    /// such as `max_displays:1`.
+    pub matcher: String,
+    pub field: String,
+    pub value: String,
+    /// Byte range of the match within `value`; absent for threshold checks.
+    pub span: Option<Range<usize>>,
+}
+
+impl MatchTrace {
+    pub fn new(matcher: &Matcher, field: &str, value: &str) -> Self {
+        Self {
+            matcher: matcher.to_string(),
+            field: field.to_string(),
+            value: value.to_string(),
+            span: matcher.find(value),
+        }
+    }
+
+    /// The matched part of `value`.
+    pub fn matched(&self) -> Option<&str> {
+        self.span.clone().and_then(|span| self.value.get(span))
+    }
+}
+
+impl fmt::Display for MatchTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} = {:?} matched {}", self.field, self.value, self.matcher)?;
+        if let (Some(span), Some(matched)) = (&self.span, self.matched()) {
+            write!(f, " at {}..{} ({:?})", span.start, span.end, matched)?;
+        }
+        Ok(())
+    }
+}
+
+/// The inventory item a rule fired on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "data", rename_all = "snake_case")]
+pub enum Evidence {
+    Process(ProcessInfo),
+    Extension(ExtensionInfo),
+    Browser(BrowserInfo),
+    NetworkAdapter(NetworkAdapterInfo),
+    HostsEntry(HostsEntry),
+    Displays(Vec<DisplayInfo>),
+    /// A display flagged as a dummy plug or capture device.
+    SuspiciousDisplay(DisplayInfo),
+    Window { title: String },
+    VirtualMachine,
+    /// A probe that failed closed, with `ProbeError::kind` and its message.
+    ProbeFailure { kind: String, message: String },
+    /// A probe the platform supports less than the policy requires.
+    Capability { probe: Probe, support: Support, required: Support },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Violation {
+    pub category: Category,
+    pub severity: Severity,
+    pub rule_id: String,
+    /// The rule's contribution to the risk score.
+    pub weight: u32,
+    /// The keyword that matched, for keyword rules.
+    pub keyword: Option<String>,
+    pub evidence: Evidence,
+    /// Filled in when the engine runs in explain mode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<MatchTrace>,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.evidence {
+            Evidence::Process(p) => write!(f, "Forbidden Process detected: {} (PID: {})", p.name, p.pid),
+            Evidence::Extension(e) => write!(f, "Suspicious Extension detected: {} ({})", e.name, e.id),
+            Evidence::Browser(b) => write!(f, "Suspicious Browser detected: {}", b.name),
+            Evidence::NetworkAdapter(a) => {
+                write!(f, "Suspicious Network Adapter detected: {} ({})", a.name, a.description)
+            }
+            Evidence::HostsEntry(h) => write!(f, "Suspicious Hosts Entry: {} -> {}", h.ip, h.domain),
+            Evidence::Displays(d) => write!(f, "Multiple Displays detected: {} monitors found", d.len()),
+            Evidence::SuspiciousDisplay(d) => match d.flag {
+                Some(flag) => write!(f, "Suspicious display detected: {} ({})", d.name, flag),
+                None => write!(f, "Suspicious display detected: {}", d.name),
+            },
+            Evidence::Window { title } => write!(f, "Forbidden Active Window detected: {}", title),
+            Evidence::VirtualMachine => f.write_str("Virtual Machine Detected"),
+            Evidence::ProbeFailure { kind, message } => write!(f, "Required check failed ({}): {}", kind, message),
+            Evidence::Capability { probe, support, required } => write!(
+                f,
+                "Required check unavailable: {} is {} on this platform, {} is required",
+                probe, support, required
+            ),
+        }
+    }
+}
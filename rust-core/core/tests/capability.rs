@@ -0,0 +1,74 @@
+use core::{Config, Evidence, PolicyEngine};
+use platform_common::{Capabilities, Probe, Support, SystemSnapshot};
+
+const POLICY: &str = r#"
+policy:
+  required_capabilities:
+    vm: supported
+    processes: partial
+  profiles:
+    practice:
+      required_capabilities:
+        vm: unsupported
+"#;
+
+fn engine(profile: &str) -> PolicyEngine {
+    PolicyEngine::from_profile(&Config::parse(POLICY).unwrap().policy.unwrap(), profile).unwrap()
+}
+
+fn windows() -> Capabilities {
+    Capabilities::new()
+        .with(Probe::Vm, Support::Partial)
+        .with(Probe::Processes, Support::Partial)
+}
+
+#[test]
+fn nothing_is_required_by_default() {
+    assert!(PolicyEngine::new().check_capabilities(&Capabilities::new()).is_empty());
+}
+
+#[test]
+fn probes_below_their_requirement_are_violations() {
+    let violations = engine("base").check_capabilities(&windows());
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].rule_id, "capability.vm");
+    assert!(matches!(
+        violations[0].evidence,
+        Evidence::Capability { probe: Probe::Vm, support: Support::Partial, required: Support::Supported }
+    ));
+    assert_eq!(
+        violations[0].to_string(),
+        "Required check unavailable: vm is partial on this platform, supported is required"
+    );
+
+    // Undeclared probes count as unsupported.
+    let ids: Vec<String> = engine("base")
+        .check_capabilities(&Capabilities::new())
+        .into_iter()
+        .map(|v| v.rule_id)
+        .collect();
+    assert_eq!(ids, ["capability.vm", "capability.processes"]);
+}
+
+#[test]
+fn profiles_can_relax_requirements() {
+    assert!(engine("practice").check_capabilities(&windows()).is_empty());
+}
+
+#[test]
+fn snapshots_are_held_to_the_requirements() {
+    let mut snapshot = SystemSnapshot::new("0.1.0");
+    // Older snapshots declared nothing and are not judged on it.
+    assert!(!engine("base").evaluate_snapshot(&snapshot).is_blocked());
+
+    snapshot.capabilities = windows();
+    let verdict = engine("base").evaluate_snapshot(&snapshot);
+    assert!(verdict.is_blocked());
+    assert_eq!(verdict.blocking[0].violation.rule_id, "capability.vm");
+}
+
+#[test]
+fn probes_without_a_category_cannot_be_required() {
+    let err = Config::parse("policy:\n  required_capabilities:\n    antivirus: supported\n").unwrap_err();
+    assert!(err.to_string().contains("`antivirus`"), "{}", err);
+}
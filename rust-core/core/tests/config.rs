@@ -0,0 +1,140 @@
+use core::{Category, Config, ConfigError, PolicyEngine, PolicyMode, Severity};
+
+const CONFIG: &str = r#"
+policy:
+  mode: audit
+  category_modes:
+    process: block
+  rules:
+    processes:
+      - "cheatengine"
+      - id: "process.remote-desktop"
+        keywords: ["anydesk", "teamviewer*"]
+        match: glob
+        severity: critical
+        weight: 80
+        fields: [name, exe_path]
+    windows: []
+    max_displays: 2
+reporting:
+  remote_endpoint: "https://reports.example.com/v1"
+"#;
+
+fn invalid(yaml: &str) -> (Option<usize>, Option<usize>, String) {
+    match Config::parse(yaml).unwrap_err() {
+        ConfigError::Invalid { line, column, message } => (line, column, message),
+        other => panic!("unexpected error: {}", other),
+    }
+}
+
+#[test]
+fn parses_bare_keywords_and_full_rules() {
+    let config = Config::parse(CONFIG).unwrap();
+    let reporting = config.reporting.unwrap();
+    assert_eq!(reporting.remote_endpoint.as_deref(), Some("https://reports.example.com/v1"));
+    let policy = config.policy.unwrap();
+    assert_eq!(policy.mode, PolicyMode::Audit);
+    assert_eq!(policy.category_modes[&Category::Process], PolicyMode::Block);
+
+    let engine = PolicyEngine::from_policy(&policy);
+    let rules = engine.rules();
+    let processes: Vec<&str> = rules.processes.iter().map(|rule| rule.id.as_str()).collect();
+    assert_eq!(processes, ["process.cheatengine", "process.remote-desktop"]);
+
+    let bare = &rules.processes[0];
+    assert_eq!(bare.severity, Category::Process.default_severity());
+    assert_eq!(bare.keywords[0].to_string(), "word:cheatengine");
+    assert!(bare.fields.is_empty());
+
+    let full = &rules.processes[1];
+    assert_eq!(full.severity, Severity::Critical);
+    assert_eq!(full.weight, 80);
+    assert_eq!(full.fields, ["name", "exe_path"]);
+    let keywords: Vec<String> = full.keywords.iter().map(ToString::to_string).collect();
+    assert_eq!(keywords, ["glob:anydesk", "glob:teamviewer*"]);
+
+    // An empty list disables the category; one left out keeps its built-ins.
+    assert!(rules.windows.is_empty());
+    assert!(!rules.browsers.is_empty());
+    assert_eq!(rules.max_displays, 2);
+}
+
+#[test]
+fn empty_config_falls_back_to_defaults() {
+    let config = Config::parse("  \n").unwrap();
+    assert!(config.policy.is_none());
+    assert!(config.reporting.is_none());
+}
+
+#[test]
+fn loads_from_a_file() {
+    let path = std::env::temp_dir().join(format!("config-{}.yaml", std::process::id()));
+    std::fs::write(&path, CONFIG).unwrap();
+    let engine = PolicyEngine::from_config(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(engine.unwrap().rules().max_displays, 2);
+}
+
+#[test]
+fn missing_file_names_the_path() {
+    let path = std::env::temp_dir().join("no-such-config.yaml");
+    let err = Config::load(&path).unwrap_err();
+    assert!(matches!(err, ConfigError::Io { .. }));
+    assert!(err.to_string().contains("no-such-config.yaml"), "{}", err);
+}
+
+#[test]
+fn unknown_fields_are_rejected_with_their_location() {
+    let (line, column, message) = invalid("policy:\n  mode: block\n  modes: audit\n");
+    assert_eq!((line, column), (Some(3), Some(3)));
+    assert!(message.contains("policy: unknown field `modes`"), "{}", message);
+
+    let (line, _, message) = invalid("policy:\n  rules:\n    processes:\n      - id: x\n        keyword: [y]\n");
+    assert_eq!(line, Some(5));
+    assert!(message.contains("unknown field `keyword`"), "{}", message);
+
+    let (line, _, message) = invalid("polcy:\n  mode: block\n");
+    assert_eq!(line, Some(1));
+    assert!(message.contains("unknown field `polcy`"), "{}", message);
+}
+
+#[test]
+fn invalid_values_are_rejected_with_their_location() {
+    let (line, column, message) = invalid("policy:\n  mode: maybe\n");
+    assert_eq!((line, column), (Some(2), Some(9)));
+    assert!(message.contains("unknown variant `maybe`"), "{}", message);
+
+    let (line, _, message) =
+        invalid("policy:\n  rules:\n    hosts:\n      - id: x\n        keywords: [y]\n        severity: dire\n");
+    assert_eq!(line, Some(6));
+    assert!(message.contains("dire"), "{}", message);
+}
+
+#[test]
+fn semantic_errors_are_reported_with_their_location() {
+    let (line, column, message) =
+        invalid("policy:\n  rules:\n    processes:\n      - id: x\n        keywords: [y]\n        fields: [name, owner]\n");
+    assert_eq!((line, column), (Some(6), Some(24)));
+    assert!(message.contains("cannot match field `owner`"), "{}", message);
+
+    let (line, _, message) = invalid("policy:\n  required_capabilities:\n    processes: partial\n    antivirus: supported\n");
+    assert_eq!(line, Some(4));
+    assert!(message.contains("`antivirus` has no category"), "{}", message);
+
+    let (line, _, message) = invalid("policy:\n  profiles:\n    a:\n      extends: b\n    b:\n      extends: missing\n");
+    assert_eq!(line, Some(6));
+    assert!(message.contains("unknown profile `missing`"), "{}", message);
+
+    let (line, _, message) = invalid("policy:\n  profiles:\n    a:\n      remove:\n        - process.cheat-tools\n        - process.nope\n");
+    assert_eq!(line, Some(6));
+    assert!(message.contains("process.nope"), "{}", message);
+}
+
+#[test]
+fn errors_display_their_location() {
+    let err = Config::parse("policy:\n  mode: block\n  modes: audit\n").unwrap_err();
+    assert!(err.to_string().starts_with("line 3, column 3: "), "{}", err);
+    // Malformed YAML is reported where the parser gave up.
+    let err = Config::parse("policy: [\n").unwrap_err();
+    assert!(err.to_string().starts_with("line "), "{}", err);
+}
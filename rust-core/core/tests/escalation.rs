@@ -0,0 +1,173 @@
+use core::{Category, Config, EscalationStep, Escalator, PolicyEngine, Verdict, ViolationLog};
+use platform_common::ProcessInfo;
+
+fn engine(yaml: &str) -> PolicyEngine {
+    PolicyEngine::from_policy(&Config::parse(yaml).unwrap().policy.unwrap_or_default())
+}
+
+fn window(engine: &PolicyEngine, title: &str) -> Verdict {
+    engine.decide(engine.check_active_window(title).into_iter().collect())
+}
+
+fn steps(escalator: &mut Escalator, verdict: &Verdict, now: u64) -> Vec<(Category, u32, EscalationStep)> {
+    escalator
+        .record(verdict, now)
+        .into_iter()
+        .map(|e| (e.category, e.strikes, e.step))
+        .collect()
+}
+
+#[test]
+fn default_ladder_warns_then_notifies_then_terminates() {
+    let engine = PolicyEngine::new();
+    let mut escalator = engine.escalator();
+    let hit = window(&engine, "ChatGPT");
+
+    assert_eq!(steps(&mut escalator, &hit, 0), [(Category::Window, 1, EscalationStep::Warn)]);
+    assert_eq!(steps(&mut escalator, &hit, 5), [(Category::Window, 2, EscalationStep::NotifyProctor)]);
+    assert_eq!(steps(&mut escalator, &hit, 10), [(Category::Window, 3, EscalationStep::Terminate)]);
+}
+
+#[test]
+fn clean_checks_add_no_strikes() {
+    let engine = PolicyEngine::new();
+    let mut escalator = engine.escalator();
+    assert!(escalator.record(&window(&engine, "Assessment"), 0).is_empty());
+    assert_eq!(escalator.strikes(Category::Window, 0), 0);
+}
+
+#[test]
+fn strikes_decay() {
+    let engine = PolicyEngine::new();
+    let mut escalator = engine.escalator();
+    let hit = window(&engine, "ChatGPT");
+
+    escalator.record(&hit, 0);
+    escalator.record(&hit, 100);
+    assert_eq!(escalator.strikes(Category::Window, 299), 2);
+    assert_eq!(escalator.strikes(Category::Window, 300), 1);
+
+    let after = escalator.record(&hit, 350);
+    assert_eq!(after[0].strikes, 2);
+    assert_eq!(after[0].step, EscalationStep::NotifyProctor);
+}
+
+#[test]
+fn a_new_offense_after_decay_starts_over_and_is_flagged() {
+    let engine = PolicyEngine::new();
+    let mut escalator = engine.escalator();
+    let hit = window(&engine, "ChatGPT");
+
+    escalator.record(&hit, 0);
+    escalator.record(&hit, 5);
+    assert_eq!(escalator.strikes(Category::Window, 400), 0);
+
+    let after = escalator.record(&hit, 400);
+    assert_eq!(after[0].strikes, 1);
+    assert_eq!(after[0].step, EscalationStep::Warn);
+    assert!(after[0].escalated);
+}
+
+#[test]
+fn an_offense_that_stays_open_strikes_once() {
+    let engine = PolicyEngine::new();
+    let mut escalator = engine.escalator();
+    let mut log = ViolationLog::new();
+    let hit = window(&engine, "ChatGPT");
+    let clean = window(&engine, "Assessment");
+
+    let struck: Vec<usize> = [&hit, &hit, &hit, &hit]
+        .into_iter()
+        .enumerate()
+        .map(|(tick, verdict)| escalator.record(&log.record(verdict), tick as u64 * 5).len())
+        .collect();
+    assert_eq!(struck, [1, 0, 0, 0]);
+    assert_eq!(escalator.strikes(Category::Window, 20), 1);
+
+    // Closing the window and opening it again is a new offense.
+    escalator.record(&log.record(&clean), 20);
+    let again = escalator.record(&log.record(&hit), 25);
+    assert_eq!(again[0].strikes, 2);
+    assert_eq!(again[0].step, EscalationStep::NotifyProctor);
+}
+
+#[test]
+fn categories_count_separately_and_can_override_the_ladder() {
+    let engine = engine(
+        r#"
+policy:
+  escalation:
+    notify_after: 3
+    terminate_after: 5
+    categories:
+      process:
+        terminate_after: 3
+"#,
+    );
+    let mut escalator = engine.escalator();
+    let processes = engine.decide(engine.check_processes(&[platform_common::ProcessInfo {
+        pid: 1,
+        name: "anydesk.exe".to_string(),
+        exe_path: None,
+        ..Default::default()
+    }]));
+    let hit = window(&engine, "ChatGPT");
+
+    for now in 0..2 {
+        escalator.record(&processes, now);
+        escalator.record(&hit, now);
+    }
+    assert_eq!(steps(&mut escalator, &processes, 2), [(Category::Process, 3, EscalationStep::Terminate)]);
+    assert_eq!(steps(&mut escalator, &hit, 2), [(Category::Window, 3, EscalationStep::NotifyProctor)]);
+}
+
+#[test]
+fn only_a_harsher_step_is_flagged_as_escalated() {
+    let engine = engine("policy:\n  escalation:\n    notify_after: 2\n    terminate_after: 10\n");
+    let mut escalator = engine.escalator();
+    let hit = window(&engine, "ChatGPT");
+    let flags: Vec<bool> = (0..4).map(|now| escalator.record(&hit, now)[0].escalated).collect();
+    assert_eq!(flags, [true, true, false, false]);
+}
+
+#[test]
+fn invalid_ladder_is_rejected() {
+    let err = Config::parse("policy:\n  escalation:\n    categories:\n      window:\n        notify_after: 5\n").unwrap_err();
+    assert!(err.to_string().contains("notify_after <= terminate_after"), "{}", err);
+}
+
+#[test]
+fn offending_processes_are_killed_once_the_process_category_terminates() {
+    let yaml = r#"
+policy:
+  escalation:
+    kill_processes: true
+    categories:
+      process:
+        notify_after: 1
+        terminate_after: 2
+"#;
+    let engine = engine(yaml);
+    let mut escalator = engine.escalator();
+    let process = |pid, name: &str| ProcessInfo {
+        pid,
+        name: name.to_string(),
+        start_time: Some(1_700_000_000),
+        ..Default::default()
+    };
+    let hit = engine.decide(engine.check_processes(&[process(10, "discord.exe"), process(11, "anydesk.exe")]));
+
+    escalator.record(&hit, 0);
+    assert!(escalator.processes_to_kill(&hit).is_empty());
+
+    escalator.record(&hit, 5);
+    let pids: Vec<_> = escalator.processes_to_kill(&hit).iter().map(|p| p.pid).collect();
+    assert_eq!(pids, [10, 11]);
+
+    // Without the setting the session ends instead.
+    let mut plain = PolicyEngine::new().escalator();
+    for now in [0, 5, 10] {
+        plain.record(&hit, now);
+    }
+    assert!(plain.processes_to_kill(&hit).is_empty());
+}
@@ -0,0 +1,119 @@
+use core::{Config, PolicyEngine};
+use platform_common::{DisplayFlag, DisplayInfo, HostsEntry, NetworkAdapterInfo, ProcessInfo};
+
+fn explaining(yaml: &str) -> PolicyEngine {
+    let mut engine = PolicyEngine::from_policy(&Config::parse(yaml).unwrap().policy.unwrap_or_default());
+    engine.set_explain(true);
+    engine
+}
+
+fn process(name: &str, exe_path: Option<&str>) -> ProcessInfo {
+    ProcessInfo {
+        pid: 3,
+        name: name.to_string(),
+        exe_path: exe_path.map(str::to_string),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn traces_are_off_by_default() {
+    let engine = PolicyEngine::new();
+    let violation = engine.check_active_window("ChatGPT - Chrome").unwrap();
+    assert!(violation.trace.is_none());
+}
+
+#[test]
+fn trace_names_matcher_field_value_and_span() {
+    let engine = explaining("");
+    let violation = engine.check_active_window("quiz - Google Search - Chrome").unwrap();
+    let trace = violation.trace.unwrap();
+    assert_eq!(violation.rule_id, "window.web-search");
+    assert_eq!(trace.matcher, "regex:(google|bing|duckduckgo) search");
+    assert_eq!(trace.field, "title");
+    assert_eq!(trace.value, "quiz - Google Search - Chrome");
+    assert_eq!(trace.span, Some(7..20));
+    assert_eq!(trace.matched(), Some("Google Search"));
+}
+
+#[test]
+fn adapter_trace_names_the_field_that_matched() {
+    let engine = explaining("");
+    let adapter = NetworkAdapterInfo {
+        name: "Ethernet 2".to_string(),
+        description: "TAP-Windows Adapter V9".to_string(),
+        mac_address: String::new(),
+        ..Default::default()
+    };
+    let hosts = [HostsEntry {
+        ip: "127.0.0.1".to_string(),
+        domain: "www.chegg.com".to_string(),
+    }];
+    let verdict = engine.evaluate(&[], &[], &[], &[], &[adapter], &hosts, "", false);
+    let traces: Vec<_> = verdict.blocking.iter().map(|g| g.violation.trace.clone().unwrap()).collect();
+    assert_eq!(traces[0].field, "description");
+    assert_eq!(traces[0].matched(), Some("TAP"));
+    assert_eq!(traces[1].field, "domain");
+    assert_eq!(traces[1].matched(), Some("chegg"));
+}
+
+#[test]
+fn threshold_checks_explain_the_limit() {
+    let engine = explaining("");
+    let displays: Vec<_> = (0..3)
+        .map(|i| DisplayInfo {
+            name: i.to_string(),
+            device_id: i.to_string(),
+            is_primary: false,
+            ..Default::default()
+        })
+        .collect();
+    let verdict = engine.evaluate(&[], &[], &displays, &[], &[], &[], "", false);
+    let trace = verdict.blocking[0].violation.trace.clone().unwrap();
+    assert_eq!(trace.matcher, "max_displays:1");
+    assert_eq!(trace.value, "3");
+    assert_eq!(trace.span, None);
+}
+
+#[test]
+fn flagged_displays_are_reported_on_their_own() {
+    // A single display is within the limit, but it is a dummy plug.
+    let displays = [DisplayInfo {
+        name: "FHD Dummy".to_string(),
+        device_id: "card0-HDMI-A-2".to_string(),
+        flag: Some(DisplayFlag::DummyPlug),
+        ..Default::default()
+    }];
+    let verdict = explaining("").evaluate(&[], &[], &displays, &[], &[], &[], "", false);
+    assert_eq!(verdict.blocking.len(), 1);
+    let violation = &verdict.blocking[0].violation;
+    assert_eq!(violation.rule_id, "display.suspicious");
+    assert_eq!(violation.trace.as_ref().unwrap().value, "FHD Dummy:dummy_plug");
+    assert_eq!(violation.to_string(), "Suspicious display detected: FHD Dummy (dummy_plug)");
+
+    let yaml = "policy:\n  profiles:\n    lenient:\n      remove: [\"display.suspicious\"]\n";
+    let engine = PolicyEngine::from_profile(&Config::parse(yaml).unwrap().policy.unwrap(), "lenient").unwrap();
+    assert!(engine.evaluate(&[], &[], &displays, &[], &[], &[], "", false).is_empty());
+}
+
+#[test]
+fn suppressed_violation_names_the_whitelist_field() {
+    let engine = explaining(
+        r#"
+policy:
+  whitelist:
+    - id: "obs-recorder-approved"
+      exe_path: "C:\\Program Files\\obs-studio\\*"
+      match: glob
+"#,
+    );
+    let processes = [process("obs64.exe", Some(r"C:\Program Files\obs-studio\bin\64bit\obs64.exe"))];
+    let verdict = engine.decide(engine.check_processes(&processes));
+    assert!(verdict.blocking.is_empty());
+    let suppression = &verdict.suppressed[0];
+    assert_eq!(suppression.allow_rule_id, "obs-recorder-approved");
+    assert_eq!(suppression.group.violation.trace.as_ref().unwrap().matched(), Some("obs"));
+    let allowed_by = suppression.allowed_by.as_ref().unwrap();
+    assert_eq!(allowed_by.field, "exe_path");
+    assert_eq!(allowed_by.matcher, r"glob:C:\Program Files\obs-studio\*");
+}
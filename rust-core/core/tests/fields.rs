@@ -0,0 +1,169 @@
+use core::{Config, PolicyEngine};
+use platform_common::{AdapterKind, BrowserFamily, ExtensionInfo, NetworkAdapterInfo, ProcessInfo};
+
+const POLICY: &str = r#"
+policy:
+  rules:
+    processes:
+      - id: "process.screen-capture"
+        keywords: ["obs"]
+        fields: [name, exe_path]
+      - id: "process.python-cheat"
+        keywords: ["autoanswer.py"]
+        match: substring
+        fields: [cmdline]
+      - id: "process.guest"
+        keywords: ["guest"]
+        match: exact
+        fields: [user]
+"#;
+
+fn engine() -> PolicyEngine {
+    let mut engine = PolicyEngine::from_policy(&Config::parse(POLICY).unwrap().policy.unwrap());
+    engine.set_explain(true);
+    engine
+}
+
+fn process(name: &str) -> ProcessInfo {
+    ProcessInfo {
+        pid: 4242,
+        name: name.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn renamed_binary_is_caught_by_exe_path() {
+    let renamed = ProcessInfo {
+        exe_path: Some("/opt/tools/obs".to_string()),
+        ..process("notes")
+    };
+    let violations = engine().check_processes(&[renamed]);
+    assert_eq!(violations.len(), 1);
+    let trace = violations[0].trace.as_ref().unwrap();
+    assert_eq!(trace.field, "exe_path");
+    assert_eq!(trace.value, "/opt/tools/obs");
+}
+
+#[test]
+fn first_listed_field_wins() {
+    let obs = ProcessInfo {
+        exe_path: Some("/usr/bin/obs".to_string()),
+        ..process("obs")
+    };
+    let violations = engine().check_processes(&[obs]);
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].trace.as_ref().unwrap().field, "name");
+}
+
+#[test]
+fn rules_match_cmdline_and_user() {
+    let script = ProcessInfo {
+        cmdline: Some("python3 /home/student/autoanswer.py --quiet".to_string()),
+        ..process("python3")
+    };
+    let guest = ProcessInfo {
+        user: Some("guest".to_string()),
+        ..process("bash")
+    };
+    let violations = engine().check_processes(&[script, guest, process("python3")]);
+    let rules: Vec<_> = violations.iter().map(|v| v.rule_id.as_str()).collect();
+    assert_eq!(rules, ["process.python-cheat", "process.guest"]);
+}
+
+#[test]
+fn unread_fields_never_match() {
+    // No exe_path, cmdline or user: only the name is checked.
+    assert!(engine().check_processes(&[process("python3")]).is_empty());
+}
+
+#[test]
+fn adapters_default_to_name_then_description() {
+    let adapter = NetworkAdapterInfo {
+        name: "Ethernet 2".to_string(),
+        description: "TAP-Windows Adapter V9".to_string(),
+        mac_address: "00:ff:12:34:56:78".to_string(),
+        ..Default::default()
+    };
+    let engine = engine();
+    let verdict = engine.evaluate(&[], &[], &[], &[], &[adapter], &[], "", false);
+    let trace = verdict.blocking[0].violation.trace.as_ref().unwrap();
+    assert_eq!(trace.field, "description");
+}
+
+#[test]
+fn unknown_fields_are_rejected() {
+    let cases = [
+        ("    processes:\n      - id: p\n        keywords: [x]\n        fields: [exe]\n", "`exe`"),
+        ("    hosts:\n      - id: h\n        keywords: [x]\n        fields: [name]\n", "hosts rules cannot match"),
+    ];
+    for (rules, expected) in cases {
+        let yaml = format!("policy:\n  rules:\n{}", rules);
+        let err = Config::parse(&yaml).unwrap_err();
+        assert!(err.to_string().contains(expected), "{}: {}", expected, err);
+    }
+    let profile = "policy:\n  profiles:\n    a:\n      rules:\n        windows:\n          - id: w\n            keywords: [x]\n            fields: [pid]\n";
+    assert!(Config::parse(profile).unwrap_err().to_string().contains("`pid`"));
+}
+
+#[test]
+fn adapter_rules_can_match_the_reported_kind() {
+    let yaml = r#"
+policy:
+  rules:
+    network_adapters:
+      - id: "network.tunnel"
+        keywords: ["tun", "tap", "wireguard"]
+        match: exact
+        fields: [kind]
+"#;
+    let engine = PolicyEngine::from_policy(&Config::parse(yaml).unwrap().policy.unwrap());
+    let adapter = |name: &str, kind| NetworkAdapterInfo {
+        name: name.to_string(),
+        kind,
+        is_up: true,
+        ..Default::default()
+    };
+    let adapters = [
+        adapter("corp0", AdapterKind::Tun),
+        adapter("tunnel-test", AdapterKind::Ethernet),
+        adapter("home", AdapterKind::WireGuard),
+    ];
+    let verdict = engine.evaluate(&[], &[], &[], &[], &adapters, &[], "", false);
+    let flagged: Vec<_> = verdict.blocking.iter().map(|g| g.subject()).collect();
+    assert_eq!(flagged, ["network:corp0", "network:home"]);
+}
+
+#[test]
+fn extension_id_rules_can_target_one_browser_family() {
+    let yaml = r#"
+policy:
+  rules:
+    extensions:
+      - id: "extension.chatgpt-firefox"
+        keywords: ["{3c6bf0cc-3ae2-42fb-9993-0d33104fdcaf}"]
+        match: exact
+        fields: [gecko_id]
+      - id: "extension.any-chromium"
+        keywords: ["chromium"]
+        match: exact
+        fields: [family]
+"#;
+    let engine = PolicyEngine::from_policy(&Config::parse(yaml).unwrap().policy.unwrap());
+    let extension = |id: &str, family| ExtensionInfo {
+        name: "Helper".to_string(),
+        id: id.to_string(),
+        family,
+        is_enabled: true,
+        ..Default::default()
+    };
+    let extensions = [
+        extension("{3c6bf0cc-3ae2-42fb-9993-0d33104fdcaf}", BrowserFamily::Gecko),
+        // Same ID string reported by a Chromium profile: only the family rule applies.
+        extension("{3c6bf0cc-3ae2-42fb-9993-0d33104fdcaf}", BrowserFamily::Chromium),
+        extension("uBlock0@raymondhill.net", BrowserFamily::Gecko),
+    ];
+    let verdict = engine.evaluate(&[], &extensions, &[], &[], &[], &[], "", false);
+    let flagged: Vec<_> = verdict.blocking.iter().map(|g| g.violation.rule_id.as_str()).collect();
+    assert_eq!(flagged, ["extension.chatgpt-firefox", "extension.any-chromium"]);
+}
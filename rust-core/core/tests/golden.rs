@@ -0,0 +1,78 @@
+//! Golden verdicts: every `tests/golden/<case>.json` snapshot is evaluated
+//! against `tests/golden/policy.yaml` and must give the verdict in
+//! `<case>.expected.yaml`.
+
+use core::{Config, PolicyEngine, RiskOutcome, Verdict, ViolationGroup, BASE_PROFILE};
+use platform_common::SystemSnapshot;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const GOLDEN: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden");
+
+/// Rule ids per verdict list, sorted. Lists left out are expected empty.
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Expected {
+    #[serde(default)]
+    profile: Option<String>,
+    outcome: RiskOutcome,
+    #[serde(default)]
+    blocking: Vec<String>,
+    #[serde(default)]
+    audited: Vec<String>,
+    #[serde(default)]
+    suppressed: Vec<String>,
+}
+
+fn rule_ids<'a>(groups: impl Iterator<Item = &'a ViolationGroup>) -> Vec<String> {
+    let mut ids: Vec<String> = groups.map(|group| group.violation.rule_id.clone()).collect();
+    ids.sort();
+    ids
+}
+
+fn actual(verdict: &Verdict, profile: Option<String>) -> Expected {
+    Expected {
+        profile,
+        outcome: verdict.risk.outcome,
+        blocking: rule_ids(verdict.blocking.iter()),
+        audited: rule_ids(verdict.audited.iter()),
+        suppressed: rule_ids(verdict.suppressed.iter().map(|s| &s.group)),
+    }
+}
+
+fn cases() -> Vec<PathBuf> {
+    let mut cases: Vec<PathBuf> = fs::read_dir(GOLDEN)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    cases.sort();
+    cases
+}
+
+fn expected(case: &Path) -> Expected {
+    let path = case.with_extension("expected.yaml");
+    let text = fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+    serde_yaml::from_str(&text).unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+}
+
+#[test]
+fn snapshots_give_their_golden_verdicts() {
+    let policy = Config::load(Path::new(GOLDEN).join("policy.yaml")).unwrap().policy.unwrap();
+    let cases = cases();
+    assert!(cases.len() >= 5, "golden corpus is missing");
+
+    let mut mismatches = Vec::new();
+    for case in &cases {
+        let expected = expected(case);
+        let profile = expected.profile.as_deref().unwrap_or(BASE_PROFILE);
+        let engine = PolicyEngine::from_profile(&policy, profile).unwrap();
+        let snapshot = SystemSnapshot::load(case).unwrap();
+        let actual = actual(&engine.evaluate_snapshot(&snapshot), expected.profile.clone());
+        if actual != expected {
+            mismatches.push(format!("{}:\n  expected {:?}\n  got      {:?}", case.display(), expected, actual));
+        }
+    }
+    assert!(mismatches.is_empty(), "{}", mismatches.join("\n"));
+}
@@ -0,0 +1,63 @@
+use core::{group_violations, PolicyEngine, ViolationLog};
+use platform_common::{ExtensionInfo, ProcessInfo};
+
+fn process(pid: u32, name: &str) -> ProcessInfo {
+    ProcessInfo {
+        pid,
+        name: name.to_string(),
+        exe_path: None,
+        ..Default::default()
+    }
+}
+
+fn extension(name: &str) -> ExtensionInfo {
+    ExtensionInfo {
+        name: name.to_string(),
+        version: "1.0".to_string(),
+        id: "abcdefghijklmnopabcdefghijklmnop".to_string(),
+        is_enabled: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn keywords_of_one_rule_collapse_into_one_group() {
+    let engine = PolicyEngine::new();
+    let verdict = engine.evaluate(&[], &[extension("ChatGPT Copilot")], &[], &[], &[], &[], "", false);
+    assert_eq!(verdict.blocking.len(), 1);
+    let group = &verdict.blocking[0];
+    assert_eq!(group.violation.rule_id, "extension.ai-assistant");
+    assert_eq!(group.keywords, ["chatgpt", "copilot"]);
+    assert_eq!(group.occurrences, 1);
+    assert_eq!(group.first_seen, group.last_seen);
+}
+
+#[test]
+fn distinct_subjects_stay_separate() {
+    let engine = PolicyEngine::new();
+    let violations = engine.check_processes(&[process(1, "obs64.exe"), process(2, "obs64.exe")]);
+    let groups = group_violations(violations, 100);
+    assert_eq!(groups.len(), 2);
+    assert_ne!(groups[0].subject(), groups[1].subject());
+}
+
+#[test]
+fn log_reports_groups_once_until_they_reappear() {
+    let engine = PolicyEngine::new();
+    let mut log = ViolationLog::new();
+    let discord = || engine.decide(engine.check_processes(&[process(7, "Discord.exe")]));
+
+    let first = log.record(&discord());
+    assert_eq!(first.blocking.len(), 1);
+    assert!(log.record(&discord()).is_empty());
+    assert!(log.record(&discord()).is_empty());
+
+    assert!(log.record(&engine.decide(vec![])).is_empty());
+    let back = log.record(&discord());
+    assert_eq!(back.blocking.len(), 1);
+    assert_eq!(back.blocking[0].occurrences, 4);
+
+    let groups: Vec<_> = log.groups().collect();
+    assert_eq!(groups.len(), 1);
+    assert!(groups[0].first_seen <= groups[0].last_seen);
+}
@@ -0,0 +1,257 @@
+use core::{MatchMode, Matcher, PolicyEngine};
+use platform_common::{AdapterKind, BrowserInfo, ExtensionInfo, HostsEntry, NetworkAdapterInfo, ProcessInfo};
+
+fn matcher(mode: MatchMode, pattern: &str) -> Matcher {
+    Matcher::new(mode, pattern).unwrap()
+}
+
+fn process(name: &str) -> ProcessInfo {
+    ProcessInfo {
+        pid: 42,
+        name: name.to_string(),
+        exe_path: None,
+        ..Default::default()
+    }
+}
+
+fn browser(name: &str) -> BrowserInfo {
+    BrowserInfo {
+        name: name.to_string(),
+        version: None,
+        install_path: String::new(),
+        profiles: vec![],
+    }
+}
+
+fn extension(name: &str) -> ExtensionInfo {
+    ExtensionInfo {
+        name: name.to_string(),
+        version: "1.0".to_string(),
+        id: "abcdefghijklmnopabcdefghijklmnop".to_string(),
+        is_enabled: true,
+        ..Default::default()
+    }
+}
+
+fn adapter(name: &str) -> NetworkAdapterInfo {
+    NetworkAdapterInfo {
+        name: name.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn word_mode_rejects_known_false_positives() {
+    let cases = [
+        ("tor", &["editor", "Monitor", "Collaborator", "Visual Studio Code - Editor", "tutorial", "Storage Explorer"][..]),
+        ("obs", &["jobs", "probsvc", "probsvc.exe", "knobs.exe", "Jobs Manager", "lobster"]),
+        ("tap", &["Realtek PCIe GbE", "Startup Apps", "metaphor"]),
+        ("tun", &["Fortune", "TuneUp"]),
+        ("gpt", &["EgptHelper"]),
+        ("cheat", &["cheatography.com"]),
+    ];
+    for (keyword, values) in cases {
+        let m = matcher(MatchMode::Word, keyword);
+        for value in values {
+            assert!(!m.is_match(value), "word `{}` should not match `{}`", keyword, value);
+        }
+    }
+}
+
+#[test]
+fn word_mode_still_matches_real_names() {
+    let cases = [
+        ("tor", &["Tor Browser", "tor.exe", "TOR"][..]),
+        ("obs", &["obs", "obs64.exe", "OBS Studio", "obs-studio", "/usr/bin/obs"]),
+        ("tap", &["TAP-Windows Adapter V9", "tap0"]),
+        ("tun", &["tun0"]),
+        ("gpt", &["GPT-4 Helper", "gpt"]),
+        ("google search", &["quiz answers - Google Search - Google Chrome"]),
+    ];
+    for (keyword, values) in cases {
+        let m = matcher(MatchMode::Word, keyword);
+        for value in values {
+            assert!(m.is_match(value), "word `{}` should match `{}`", keyword, value);
+        }
+    }
+}
+
+#[test]
+fn word_mode_reports_span_of_bounded_match() {
+    let m = matcher(MatchMode::Word, "obs");
+    assert_eq!(m.find("jobs obs64.exe"), Some(5..8));
+}
+
+#[test]
+fn exact_prefix_glob_and_regex_modes() {
+    assert!(matcher(MatchMode::Exact, "discord.exe").is_match("Discord.exe"));
+    assert!(!matcher(MatchMode::Exact, "discord.exe").is_match("discord.exe.bak"));
+
+    assert!(matcher(MatchMode::Prefix, "obs").is_match("obs64.exe"));
+    assert!(!matcher(MatchMode::Prefix, "obs").is_match("jobs"));
+
+    let glob = matcher(MatchMode::Glob, "obs*.exe");
+    assert!(glob.is_match("OBS64.exe"));
+    assert!(!glob.is_match("jobs.exe"));
+    assert!(!glob.is_match("obs64.exe.log"));
+    assert!(matcher(MatchMode::Glob, "tun[0-9]").is_match("tun0"));
+    assert!(!matcher(MatchMode::Glob, "tun[!0-9]").is_match("tun0"));
+
+    let regex = matcher(MatchMode::Regex, r"^cheat\s*engine");
+    assert!(regex.is_match("Cheat Engine 7.5"));
+    assert!(!regex.is_match("anticheat engine"));
+}
+
+#[test]
+fn substring_mode_keeps_legacy_behavior() {
+    assert!(matcher(MatchMode::Substring, "tor").is_match("editor"));
+}
+
+#[test]
+fn invalid_patterns_are_rejected() {
+    assert!(Matcher::new(MatchMode::Regex, "(unclosed").is_err());
+    assert!(Matcher::new(MatchMode::Glob, "tun[0-9").is_err());
+}
+
+#[test]
+fn default_rules_ignore_false_positive_inventory() {
+    let engine = PolicyEngine::new();
+
+    let processes: Vec<_> = ["jobs", "probsvc.exe", "knobs.exe", "editor.exe", "monitor"]
+        .into_iter()
+        .map(process)
+        .collect();
+    assert!(engine.check_processes(&processes).is_empty());
+
+    for title in ["Research notes.docx - Word", "Google Docs", "Collaborator - Chrome", "Job search tips"] {
+        assert!(engine.check_active_window(title).is_none(), "window `{}` flagged", title);
+    }
+
+    let verdict = engine.evaluate(
+        &[browser("Visual Studio Code Editor"), browser("Monitor Browser")],
+        &[extension("Jobs Tracker")],
+        &[],
+        &[],
+        &[],
+        &[],
+        "",
+        false,
+    );
+    assert!(verdict.is_empty(), "unexpected violations: {:?}", verdict);
+}
+
+#[test]
+fn default_rules_flag_real_tools() {
+    let engine = PolicyEngine::new();
+    let violations = engine.check_processes(&[process("obs64.exe"), process("Discord.exe")]);
+    let rules: Vec<_> = violations.iter().map(|v| v.rule_id.as_str()).collect();
+    assert_eq!(rules, ["process.screen-capture", "process.communication"]);
+
+    let window = engine.check_active_window("quiz - Google Search - Google Chrome").unwrap();
+    assert_eq!(window.rule_id, "window.web-search");
+}
+
+#[test]
+fn default_rules_flag_compound_product_names() {
+    let engine = PolicyEngine::new();
+    let cases = [
+        ("NordVPN", "extension.tunnel"),
+        ("ProtonVPN", "extension.tunnel"),
+        ("FoxyProxy Standard", "extension.tunnel"),
+        ("HackBar", "extension.security-tools"),
+        ("WebChatGPT: ChatGPT with internet access", "extension.ai-assistant"),
+    ];
+    for (name, rule) in cases {
+        let verdict = engine.evaluate(&[], &[extension(name)], &[], &[], &[], &[], "", false);
+        let rules: Vec<_> = verdict.blocking.iter().map(|g| g.violation.rule_id.as_str()).collect();
+        assert_eq!(rules, [rule], "extension `{}`", name);
+    }
+
+    for name in ["utun3", "NordVPN", "ProtonVPN"] {
+        let verdict = engine.evaluate(&[], &[], &[], &[], &[adapter(name)], &[], "", false);
+        let rules: Vec<_> = verdict.blocking.iter().map(|g| g.violation.rule_id.as_str()).collect();
+        assert_eq!(rules, ["network.vpn"], "adapter `{}`", name);
+    }
+}
+
+#[test]
+fn default_rules_flag_tunnel_kinds_that_are_up() {
+    let engine = PolicyEngine::new();
+    let cases = [
+        (AdapterKind::WireGuard, true, vec!["network.vpn-kind"]),
+        (AdapterKind::Tun, true, vec!["network.vpn-kind"]),
+        (AdapterKind::Tap, true, vec!["network.vpn-kind"]),
+        (AdapterKind::WireGuard, false, vec![]),
+        (AdapterKind::Ethernet, true, vec![]),
+    ];
+    for (kind, is_up, expected) in cases {
+        let wg0 = NetworkAdapterInfo {
+            kind,
+            is_up,
+            ..adapter("wg0")
+        };
+        let violations = engine.evaluate(&[], &[], &[], &[], &[wg0], &[], "", false);
+        let rules: Vec<_> = violations.blocking.iter().map(|g| g.violation.rule_id.as_str()).collect();
+        assert_eq!(rules, expected, "{:?} up={}", kind, is_up);
+    }
+}
+
+#[test]
+fn built_in_web_search_rule_matches_the_example_config() {
+    let engine = PolicyEngine::new();
+    for title in ["quiz - Bing Search", "answers - DuckDuckGo Search", "Stack Overflow - Firefox"] {
+        assert!(engine.check_active_window(title).is_some(), "window `{}` not flagged", title);
+    }
+    assert!(engine.check_active_window("Bing Maps").is_none());
+}
+
+#[test]
+fn config_selects_match_mode_per_rule() {
+    let config = core::Config::parse(
+        r#"
+policy:
+  rules:
+    processes:
+      - id: "process.obs"
+        keywords: ["obs*.exe"]
+        match: glob
+"#,
+    )
+    .unwrap();
+    let engine = PolicyEngine::from_policy(&config.policy.unwrap());
+    assert_eq!(engine.check_processes(&[process("obs64.exe")]).len(), 1);
+    assert!(engine.check_processes(&[process("obs")]).is_empty());
+}
+
+#[test]
+fn config_reports_bad_regex_with_line() {
+    let err = core::Config::parse(
+        "policy:\n  rules:\n    windows:\n      - id: bad\n        keywords: [\"(\"]\n        match: regex\n",
+    )
+    .unwrap_err();
+    match err {
+        core::ConfigError::Invalid { line, message, .. } => {
+            assert_eq!(line, Some(4));
+            assert!(message.contains("invalid regex pattern"), "{}", message);
+        }
+        other => panic!("unexpected error: {}", other),
+    }
+}
+
+#[test]
+fn blacklist_applies_to_every_keyword_category() {
+    let yaml = "policy:\n  blacklist: [\"perplexity\"]\n";
+    let engine = PolicyEngine::from_policy(&core::Config::parse(yaml).unwrap().policy.unwrap());
+    let adapters = [NetworkAdapterInfo {
+        description: "Perplexity Relay".to_string(),
+        ..adapter("relay0")
+    }];
+    let hosts = [HostsEntry {
+        ip: "10.0.0.1".to_string(),
+        domain: "api.perplexity.ai".to_string(),
+    }];
+    let verdict = engine.evaluate(&[], &[], &[], &[process("perplexity")], &adapters, &hosts, "Perplexity", false);
+    let categories: Vec<String> = verdict.blocking.iter().map(|g| g.violation.category.to_string()).collect();
+    assert_eq!(categories, ["process", "network", "hosts", "window"]);
+    assert!(verdict.blocking.iter().all(|g| g.violation.rule_id == "blacklist"));
+}
@@ -0,0 +1,66 @@
+use core::{Category, Config, PolicyEngine, Verdict, ViolationGroup};
+use platform_common::ProcessInfo;
+
+fn engine(yaml: &str) -> PolicyEngine {
+    PolicyEngine::from_policy(&Config::parse(yaml).unwrap().policy.unwrap_or_default())
+}
+
+/// One process and one window violation, decided together.
+fn decide(engine: &PolicyEngine) -> Verdict {
+    let process = ProcessInfo {
+        pid: 42,
+        name: "cheatengine".to_string(),
+        ..Default::default()
+    };
+    let mut violations = engine.check_processes(&[process]);
+    violations.extend(engine.check_active_window("Discord"));
+    engine.decide(violations)
+}
+
+fn categories(groups: &[ViolationGroup]) -> Vec<Category> {
+    groups.iter().map(|g| g.violation.category).collect()
+}
+
+#[test]
+fn block_is_the_default_mode() {
+    let verdict = decide(&PolicyEngine::new());
+    assert_eq!(categories(&verdict.blocking), [Category::Process, Category::Window]);
+    assert!(verdict.audited.is_empty());
+    assert!(verdict.is_blocked());
+}
+
+#[test]
+fn audit_reports_without_blocking() {
+    let verdict = decide(&engine("policy:\n  mode: audit\n"));
+    assert!(verdict.blocking.is_empty());
+    assert_eq!(categories(&verdict.audited), [Category::Process, Category::Window]);
+    assert!(!verdict.is_blocked());
+    assert!(!verdict.is_empty());
+}
+
+#[test]
+fn allow_drops_violations_from_the_verdict() {
+    let engine = engine("policy:\n  mode: allow\n");
+    // The category is still profiled: the rules fire, the verdict drops them.
+    assert!(engine.check_active_window("Discord").is_some());
+    let verdict = decide(&engine);
+    assert!(verdict.is_empty());
+    assert!(verdict.suppressed.is_empty());
+    assert!(!verdict.is_blocked());
+    assert_eq!(verdict.risk.score, 0);
+}
+
+#[test]
+fn category_modes_override_the_policy_mode() {
+    let verdict = decide(&engine("policy:\n  mode: audit\n  category_modes:\n    process: block\n"));
+    assert_eq!(categories(&verdict.blocking), [Category::Process]);
+    assert_eq!(categories(&verdict.audited), [Category::Window]);
+    assert!(verdict.is_blocked());
+
+    let verdict = decide(&engine("policy:\n  category_modes:\n    process: allow\n    window: audit\n"));
+    assert!(verdict.blocking.is_empty());
+    assert_eq!(categories(&verdict.audited), [Category::Window]);
+
+    let err = Config::parse("policy:\n  category_modes:\n    process: warn\n").unwrap_err();
+    assert!(err.to_string().contains("unknown variant `warn`"), "{}", err);
+}
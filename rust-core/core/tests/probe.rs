@@ -0,0 +1,107 @@
+use core::{Category, Config, Evidence, PolicyEngine, ProbeAction};
+use platform_common::{Probe, ProbeError};
+use std::time::Duration;
+
+const POLICY: &str = r#"
+policy:
+  category_modes:
+    window: audit
+  probes:
+    vm:
+      on_error: fail_closed
+    window:
+      on_error: fail_closed
+      on_unsupported: fail_closed
+  profiles:
+    lenient:
+      probes:
+        vm: {}
+"#;
+
+fn engine(profile: &str) -> PolicyEngine {
+    PolicyEngine::from_profile(&Config::parse(POLICY).unwrap().policy.unwrap(), profile).unwrap()
+}
+
+fn vm_error() -> ProbeError {
+    ProbeError::Unavailable {
+        message: "no VM signal source was readable under /".to_string(),
+    }
+}
+
+#[test]
+fn probes_fail_open_by_default() {
+    let engine = PolicyEngine::new();
+    assert_eq!(engine.probe_policy(Category::VM).on_error, ProbeAction::FailOpen);
+    assert!(engine.check_probe(Category::VM, &vm_error()).is_none());
+}
+
+#[test]
+fn failed_vm_check_can_fail_closed() {
+    let engine = engine("base");
+    let violation = engine.check_probe(Category::VM, &vm_error()).unwrap();
+    assert_eq!(violation.rule_id, "probe.vm");
+    assert!(matches!(&violation.evidence, Evidence::ProbeFailure { kind, .. } if kind == "unavailable"));
+
+    let verdict = engine.decide(vec![violation]);
+    assert_eq!(verdict.blocking.len(), 1);
+    assert!(verdict.is_blocked());
+}
+
+#[test]
+fn unsupported_probes_are_a_separate_decision() {
+    let engine = engine("base");
+    let unsupported = ProbeError::Unsupported {
+        probe: "VM detection",
+        platform: "macOS",
+    };
+    assert!(engine.check_probe(Category::VM, &unsupported).is_none());
+
+    // Still subject to the category mode.
+    let window = ProbeError::Unsupported {
+        probe: "active window",
+        platform: "Linux",
+    };
+    let verdict = engine.decide(engine.check_probe(Category::Window, &window).into_iter().collect());
+    assert!(verdict.blocking.is_empty());
+    assert_eq!(verdict.audited.len(), 1);
+}
+
+#[test]
+fn profiles_override_probe_settings() {
+    assert!(engine("lenient").check_probe(Category::VM, &vm_error()).is_none());
+}
+
+#[test]
+fn explain_mode_names_the_setting() {
+    let mut engine = engine("base");
+    engine.set_explain(true);
+    let trace = engine.check_probe(Category::VM, &vm_error()).unwrap().trace.unwrap();
+    assert_eq!(trace.matcher, "on_error:fail_closed");
+    assert_eq!(trace.field, "probe");
+    assert_eq!(trace.value, vm_error().to_string());
+}
+
+#[test]
+fn unknown_probe_actions_are_rejected() {
+    let err = Config::parse("policy:\n  probes:\n    vm:\n      on_error: retry\n").unwrap_err();
+    assert!(err.to_string().contains("fail_closed"), "{}", err);
+}
+
+#[test]
+fn timed_out_probes_count_as_errors() {
+    let timed_out = ProbeError::TimedOut {
+        probe: "vm",
+        after: Duration::from_secs(5),
+    };
+    let violation = engine("base").check_probe(Category::VM, &timed_out).unwrap();
+    assert!(matches!(&violation.evidence, Evidence::ProbeFailure { kind, .. } if kind == "timed_out"));
+    assert!(engine("lenient").check_probe(Category::VM, &timed_out).is_none());
+}
+
+#[test]
+fn preflight_deadlines_are_configurable() {
+    let config = Config::parse("preflight:\n  timeout_ms: 3000\n  probe_timeouts_ms:\n    extensions: 20000\n").unwrap();
+    let deadlines = config.preflight.unwrap();
+    assert_eq!(deadlines.deadline(Probe::Extensions), Duration::from_secs(20));
+    assert_eq!(deadlines.deadline(Probe::Processes), Duration::from_secs(3));
+}
@@ -0,0 +1,121 @@
+use core::{Config, PolicyConfig, PolicyEngine, BASE_PROFILE};
+use platform_common::{DisplayInfo, ProcessInfo};
+
+const POLICY: &str = r#"
+policy:
+  whitelist:
+    - id: "notepad"
+      process_name: "notepad"
+  profiles:
+    open-book:
+      exams: ["HIST-210"]
+      rules:
+        processes:
+          - id: "process.ebook-sync"
+            keywords: ["calibre-sync"]
+      remove: ["notepad"]
+    coding:
+      exams: ["CS101-final", "CS201-midterm"]
+      whitelist:
+        - id: "vscode"
+          process_name: "code"
+      remove: ["process.communication"]
+    coding-dual-screen:
+      extends: coding
+      exams: ["CS301-lab"]
+      remove: ["display.multiple"]
+"#;
+
+fn policy() -> PolicyConfig {
+    Config::parse(POLICY).unwrap().policy.unwrap()
+}
+
+fn process(name: &str) -> ProcessInfo {
+    ProcessInfo {
+        pid: 9,
+        name: name.to_string(),
+        exe_path: None,
+        ..Default::default()
+    }
+}
+
+fn displays(count: usize) -> Vec<DisplayInfo> {
+    (0..count)
+        .map(|i| DisplayInfo {
+            name: format!("display {}", i),
+            device_id: i.to_string(),
+            is_primary: i == 0,
+            ..Default::default()
+        })
+        .collect()
+}
+
+#[test]
+fn exam_id_selects_profile() {
+    let policy = policy();
+    assert_eq!(policy.profile_for_exam("CS201-midterm").unwrap(), "coding");
+    assert_eq!(policy.profile_for_exam("CS301-lab").unwrap(), "coding-dual-screen");
+    let err = policy.profile_for_exam("MATH-100").unwrap_err();
+    assert_eq!(err.to_string(), "no profile lists exam `MATH-100`");
+}
+
+#[test]
+fn profile_adds_and_removes_rules() {
+    let policy = policy();
+    let base = PolicyEngine::from_policy(&policy);
+    let coding = PolicyEngine::from_profile(&policy, "coding").unwrap();
+    let open_book = PolicyEngine::from_profile(&policy, "open-book").unwrap();
+
+    assert_eq!(base.check_processes(&[process("slack.exe")]).len(), 1);
+    assert!(coding.check_processes(&[process("slack.exe")]).is_empty());
+
+    assert!(base.check_processes(&[process("calibre-sync")]).is_empty());
+    assert_eq!(open_book.check_processes(&[process("calibre-sync")])[0].rule_id, "process.ebook-sync");
+
+    assert!(base.whitelist().iter().any(|entry| entry.id == "notepad"));
+    assert!(!open_book.whitelist().iter().any(|entry| entry.id == "notepad"));
+    assert!(coding.whitelist().iter().any(|entry| entry.id == "vscode"));
+}
+
+#[test]
+fn profiles_inherit_through_extends() {
+    let policy = policy();
+    let dual = PolicyEngine::from_profile(&policy, "coding-dual-screen").unwrap();
+    assert!(dual.check_processes(&[process("slack.exe")]).is_empty());
+    assert!(dual.whitelist().iter().any(|entry| entry.id == "vscode"));
+
+    let verdict = dual.evaluate(&[], &[], &displays(2), &[], &[], &[], "", false);
+    assert!(verdict.is_empty());
+
+    let coding = PolicyEngine::from_profile(&policy, "coding").unwrap();
+    let verdict = coding.evaluate(&[], &[], &displays(2), &[], &[], &[], "", false);
+    assert_eq!(verdict.blocking.len(), 1);
+}
+
+#[test]
+fn verdict_names_the_profile() {
+    let policy = policy();
+    let verdict = PolicyEngine::from_profile(&policy, "coding").unwrap().decide(vec![]);
+    assert_eq!(verdict.profile, "coding");
+    assert_eq!(PolicyEngine::from_policy(&policy).decide(vec![]).profile, BASE_PROFILE);
+}
+
+#[test]
+fn invalid_profiles_are_rejected() {
+    let cases = [
+        ("policy:\n  profiles:\n    a:\n      extends: missing\n", "unknown profile `missing`"),
+        ("policy:\n  profiles:\n    a:\n      extends: b\n    b:\n      extends: a\n", "cycle: a -> b -> a"),
+        ("policy:\n  profiles:\n    a:\n      extends: a\n", "cycle: a -> a"),
+        (
+            "policy:\n  profiles:\n    a:\n      extends: b\n    b:\n      extends: c\n    c:\n      extends: b\n",
+            "cycle: b -> c -> b",
+        ),
+        ("policy:\n  profiles:\n    a:\n      exams: [X]\n    b:\n      exams: [X]\n", "exam `X`"),
+        ("policy:\n  profiles:\n    base: {}\n", "reserved"),
+        ("policy:\n  profiles:\n    a:\n      remove: [process.nope]\n", "process.nope"),
+    ];
+    for (yaml, expected) in cases {
+        let err = Config::parse(yaml).unwrap_err();
+        assert!(err.to_string().contains(expected), "{}: {}", expected, err);
+    }
+}
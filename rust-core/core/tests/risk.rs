@@ -0,0 +1,152 @@
+use core::{Config, PolicyEngine, RiskOutcome, RiskThresholds, SessionRisk};
+use platform_common::{DisplayInfo, ProcessInfo};
+
+fn process(name: &str) -> ProcessInfo {
+    ProcessInfo {
+        pid: 42,
+        name: name.to_string(),
+        exe_path: None,
+        ..Default::default()
+    }
+}
+
+fn display(name: &str) -> DisplayInfo {
+    DisplayInfo {
+        name: name.to_string(),
+        device_id: name.to_string(),
+        is_primary: false,
+        ..Default::default()
+    }
+}
+
+fn engine(yaml: &str) -> PolicyEngine {
+    PolicyEngine::from_policy(&Config::parse(yaml).unwrap().policy.unwrap_or_default())
+}
+
+#[test]
+fn thresholds_map_scores_to_outcomes() {
+    let thresholds = RiskThresholds::default();
+    assert_eq!(thresholds.outcome(0), RiskOutcome::Pass);
+    assert_eq!(thresholds.outcome(10), RiskOutcome::Warn);
+    assert_eq!(thresholds.outcome(25), RiskOutcome::Review);
+    assert_eq!(thresholds.outcome(50), RiskOutcome::Terminate);
+}
+
+#[test]
+fn second_display_weighs_less_than_cheat_engine() {
+    let engine = PolicyEngine::new();
+    let displays = engine.evaluate(&[], &[], &[display("a"), display("b")], &[], &[], &[], "", false);
+    assert_eq!(displays.risk.score, 25);
+    assert_eq!(displays.risk.outcome, RiskOutcome::Review);
+
+    let cheat = engine.evaluate(&[], &[], &[], &[process("cheatengine-x86_64.exe")], &[], &[], "", false);
+    assert_eq!(cheat.risk.score, 100);
+    assert!(cheat.is_blocked());
+    assert_eq!(cheat.risk.contributions[0].rule_id, "process.cheat-tools");
+}
+
+#[test]
+fn rule_counts_once_per_snapshot() {
+    let engine = PolicyEngine::new();
+    let verdict = engine.evaluate(&[], &[], &[], &[process("obs64.exe"), process("obs32.exe")], &[], &[], "", false);
+    assert_eq!(verdict.risk.score, 50);
+    assert_eq!(verdict.risk.contributions[0].occurrences, 2);
+}
+
+#[test]
+fn config_overrides_thresholds_and_weights() {
+    let engine = engine(
+        r#"
+policy:
+  risk:
+    thresholds: { warn: 5, review: 40, terminate: 200 }
+    weights:
+      display.multiple: 5
+      process.cheat-tools: 150
+"#,
+    );
+    let displays = engine.evaluate(&[], &[], &[display("a"), display("b")], &[], &[], &[], "", false);
+    assert_eq!(displays.risk.score, 5);
+    assert_eq!(displays.risk.outcome, RiskOutcome::Warn);
+
+    let cheat = engine.evaluate(&[], &[], &[], &[process("cheatengine")], &[], &[], "", false);
+    assert_eq!(cheat.risk.score, 150);
+    assert_eq!(cheat.risk.outcome, RiskOutcome::Review);
+    assert!(!cheat.is_blocked());
+}
+
+#[test]
+fn a_low_weight_block_violation_only_warns() {
+    let engine = engine("policy:\n  risk:\n    weights:\n      display.multiple: 20\n");
+    let displays = engine.evaluate(&[], &[], &[display("a"), display("b")], &[], &[], &[], "", false);
+    assert_eq!(displays.blocking.len(), 1);
+    assert_eq!(displays.risk.outcome, RiskOutcome::Warn);
+    assert!(!displays.is_blocked());
+}
+
+#[test]
+fn score_at_terminate_blocks() {
+    let engine = PolicyEngine::new();
+    // Two medium rules in block categories add up to the terminate threshold.
+    let verdict = engine.evaluate(
+        &[],
+        &[],
+        &[display("a"), display("b")],
+        &[],
+        &[],
+        &[],
+        "quiz - Google Search",
+        false,
+    );
+    assert_eq!(verdict.risk.score, engine.thresholds().terminate);
+    assert_eq!(verdict.risk.outcome, RiskOutcome::Terminate);
+    assert!(verdict.is_blocked());
+}
+
+#[test]
+fn blocked_verdicts_always_terminate() {
+    let engine = PolicyEngine::new();
+    let checks = [
+        engine.evaluate(&[], &[], &[display("a"), display("b")], &[], &[], &[], "", false),
+        engine.evaluate(&[], &[], &[], &[process("cheatengine")], &[], &[], "", false),
+        engine.evaluate(&[], &[], &[], &[process("obs64.exe")], &[], &[], "Discord", false),
+        engine.evaluate(&[], &[], &[], &[], &[], &[], "", true),
+    ];
+    for verdict in &checks {
+        assert_eq!(verdict.is_blocked(), verdict.risk.outcome == RiskOutcome::Terminate, "{:?}", verdict.risk);
+    }
+    assert_eq!(checks.iter().filter(|verdict| verdict.is_blocked()).count(), 3);
+}
+
+#[test]
+fn audited_violations_score_but_never_block() {
+    let engine = engine("policy:\n  category_modes:\n    process: audit\n");
+    let verdict = engine.evaluate(&[], &[], &[], &[process("cheatengine")], &[], &[], "", false);
+    assert_eq!(verdict.audited.len(), 1);
+    assert_eq!(verdict.risk.score, 100);
+    assert_eq!(verdict.risk.outcome, RiskOutcome::Review);
+    assert!(!verdict.is_blocked());
+}
+
+#[test]
+fn session_score_accumulates_across_checks() {
+    let engine = PolicyEngine::new();
+    let mut session: SessionRisk = engine.session_risk();
+    let window = engine.decide(engine.check_active_window("Discord").into_iter().collect());
+    session.record(&window);
+    session.record(&window);
+    assert_eq!(session.assessment().score, 25);
+    assert_eq!(session.assessment().outcome, RiskOutcome::Review);
+    assert!(!window.is_blocked());
+
+    let search = engine.decide(engine.check_active_window("quiz - Google Search").into_iter().collect());
+    session.record(&search);
+    assert_eq!(session.assessment().score, 50);
+    assert_eq!(session.assessment().outcome, RiskOutcome::Terminate);
+}
+
+#[test]
+fn invalid_thresholds_are_rejected() {
+    let err = Config::parse("policy:\n  risk:\n    thresholds: { warn: 30, review: 20, terminate: 50 }\n").unwrap_err();
+    assert!(err.to_string().contains("warn <= review"), "{}", err);
+}
@@ -0,0 +1,57 @@
+use core::{Category, Config, EscalationStep, PolicyEngine};
+use platform_common::{FixtureProfiler, ProcessScanner, SystemProfiler};
+use std::time::Duration;
+
+const SESSION: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/anydesk-mid-session.json");
+
+/// Runs the agent's 5-second runtime check over the fixture's schedule and
+/// returns the process escalations of every tick.
+fn process_steps(engine: &PolicyEngine) -> Vec<(u64, Option<(u32, EscalationStep)>)> {
+    let fixture = FixtureProfiler::load(SESSION).unwrap();
+    let mut escalator = engine.escalator();
+    (5..=30)
+        .step_by(5)
+        .map(|secs| {
+            let probes = fixture.clone().at(Duration::from_secs(secs));
+            let mut violations = Vec::new();
+            match probes.get_active_window() {
+                Ok(title) => violations.extend(engine.check_active_window(&title)),
+                Err(e) => violations.extend(engine.check_probe(Category::Window, &e)),
+            }
+            match probes.scan() {
+                Ok(processes) => violations.extend(engine.check_processes(&processes)),
+                Err(e) => violations.extend(engine.check_probe(Category::Process, &e)),
+            }
+            let verdict = engine.decide(violations);
+            let step = escalator
+                .record(&verdict, 1_760_000_000 + secs)
+                .into_iter()
+                .find(|e| e.category == Category::Process)
+                .map(|e| (e.strikes, e.step));
+            (secs, step)
+        })
+        .collect()
+}
+
+#[test]
+fn scripted_session_escalates_while_the_tool_runs() {
+    assert_eq!(
+        process_steps(&PolicyEngine::new()),
+        [
+            (5, None),
+            (10, None),
+            (15, Some((1, EscalationStep::Warn))),
+            (20, Some((2, EscalationStep::NotifyProctor))),
+            (25, None),
+            // The scan fails, and fails open by default.
+            (30, None),
+        ]
+    );
+}
+
+#[test]
+fn failing_scan_counts_as_a_strike_when_it_fails_closed() {
+    let policy = Config::parse("policy:\n  probes:\n    process: { on_error: fail_closed }\n").unwrap().policy.unwrap();
+    let steps = process_steps(&PolicyEngine::from_policy(&policy));
+    assert_eq!(steps.last(), Some(&(30, Some((3, EscalationStep::Terminate)))));
+}
@@ -0,0 +1,82 @@
+use core::{Config, PolicyEngine};
+use platform_common::{DisplayInfo, Probe, ProbeStatus, ProcessInfo, SystemSnapshot, VmDetection, VmSignal};
+
+fn snapshot() -> SystemSnapshot {
+    let mut snapshot = SystemSnapshot::new("0.1.0");
+    snapshot.processes = vec![ProcessInfo {
+        pid: 42,
+        name: "AnyDesk.exe".to_string(),
+        ..Default::default()
+    }];
+    snapshot.displays = vec![DisplayInfo::default(), DisplayInfo::default()];
+    snapshot.active_window = Some("OLL Assessment".to_string());
+    snapshot.vm = Some(VmDetection::from_signals(vec![VmSignal::new("dmi:sys_vendor", "QEMU", 90)]));
+    for probe in [Probe::Processes, Probe::Displays, Probe::ActiveWindow, Probe::Vm] {
+        snapshot.probes.insert(probe, ProbeStatus::Ok);
+    }
+    snapshot
+}
+
+fn rule_ids(engine: &PolicyEngine, snapshot: &SystemSnapshot) -> Vec<String> {
+    let mut ids: Vec<String> = engine
+        .evaluate_snapshot(snapshot)
+        .blocking
+        .iter()
+        .map(|group| group.violation.rule_id.clone())
+        .collect();
+    ids.sort();
+    ids
+}
+
+#[test]
+fn snapshot_is_evaluated_like_its_inventory() {
+    let engine = PolicyEngine::new();
+    let snapshot = snapshot();
+    let direct = engine.evaluate(&[], &[], &snapshot.displays, &snapshot.processes, &[], &[], "OLL Assessment", true);
+    let replayed = engine.evaluate_snapshot(&snapshot);
+    assert_eq!(replayed.risk.score, direct.risk.score);
+    assert_eq!(replayed.blocking.len(), direct.blocking.len());
+    assert_eq!(rule_ids(&engine, &snapshot), ["display.multiple", "process.remote-access", "vm.detected"]);
+}
+
+#[test]
+fn failed_probes_in_a_snapshot_follow_the_probe_policy() {
+    let mut snapshot = snapshot();
+    snapshot.vm = None;
+    snapshot.probes.insert(
+        Probe::Vm,
+        ProbeStatus::Failed {
+            kind: "unavailable".to_string(),
+            message: "no VM signal source was readable under /".to_string(),
+        },
+    );
+    // Antivirus failures have no category and never count.
+    snapshot.probes.insert(
+        Probe::Antivirus,
+        ProbeStatus::Unsupported {
+            message: "antivirus inventory is not supported on Linux".to_string(),
+        },
+    );
+
+    let open = PolicyEngine::new();
+    assert_eq!(rule_ids(&open, &snapshot), ["display.multiple", "process.remote-access"]);
+
+    let policy = Config::parse("policy:\n  probes:\n    vm: { on_error: fail_closed }\n").unwrap().policy.unwrap();
+    let closed = PolicyEngine::from_policy(&policy);
+    assert_eq!(rule_ids(&closed, &snapshot), ["display.multiple", "probe.vm", "process.remote-access"]);
+}
+
+#[test]
+fn saved_snapshots_can_be_replayed_against_a_newer_policy() {
+    let path = std::env::temp_dir().join(format!("replay-{}.json", std::process::id()));
+    snapshot().save(&path).unwrap();
+    let replayed = SystemSnapshot::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let newer = Config::parse("policy:\n  rules:\n    max_displays: 2\n  profiles:\n    vm-ok:\n      remove: [\"vm.detected\"]\n")
+        .unwrap()
+        .policy
+        .unwrap();
+    let engine = PolicyEngine::from_profile(&newer, "vm-ok").unwrap();
+    assert_eq!(rule_ids(&engine, &replayed), ["process.remote-access"]);
+}
@@ -0,0 +1,110 @@
+use core::{Category, Evidence, Severity, Violation};
+use platform_common::{
+    BrowserInfo, DisplayFlag, DisplayInfo, ExtensionInfo, HostsEntry, NetworkAdapterInfo, Probe, ProcessInfo, Support,
+};
+
+fn violation(evidence: Evidence) -> Violation {
+    Violation {
+        category: Category::Process,
+        severity: Severity::High,
+        rule_id: "test".to_string(),
+        weight: 50,
+        keyword: None,
+        evidence,
+        trace: None,
+    }
+}
+
+fn display(name: &str) -> DisplayInfo {
+    DisplayInfo {
+        name: name.to_string(),
+        ..Default::default()
+    }
+}
+
+// The agent prints these texts and they end up in reports.
+#[test]
+fn every_evidence_kind_has_a_fixed_text() {
+    let cases = [
+        (
+            Evidence::Process(ProcessInfo {
+                pid: 4242,
+                name: "cheatengine.exe".to_string(),
+                ..Default::default()
+            }),
+            "Forbidden Process detected: cheatengine.exe (PID: 4242)",
+        ),
+        (
+            Evidence::Extension(ExtensionInfo {
+                name: "ChatGPT for Google".to_string(),
+                id: "jgjaeacdkonaoafenlfkkkmbaopkbilf".to_string(),
+                ..Default::default()
+            }),
+            "Suspicious Extension detected: ChatGPT for Google (jgjaeacdkonaoafenlfkkkmbaopkbilf)",
+        ),
+        (
+            Evidence::Browser(BrowserInfo {
+                name: "Tor Browser".to_string(),
+                version: None,
+                install_path: String::new(),
+                profiles: vec![],
+            }),
+            "Suspicious Browser detected: Tor Browser",
+        ),
+        (
+            Evidence::NetworkAdapter(NetworkAdapterInfo {
+                name: "tun0".to_string(),
+                description: "WireGuard Tunnel".to_string(),
+                ..Default::default()
+            }),
+            "Suspicious Network Adapter detected: tun0 (WireGuard Tunnel)",
+        ),
+        (
+            Evidence::HostsEntry(HostsEntry {
+                ip: "127.0.0.1".to_string(),
+                domain: "proctor.example.com".to_string(),
+            }),
+            "Suspicious Hosts Entry: 127.0.0.1 -> proctor.example.com",
+        ),
+        (
+            Evidence::Displays(vec![display("DELL U2720Q"), display("LG 27UL500")]),
+            "Multiple Displays detected: 2 monitors found",
+        ),
+        (
+            Evidence::SuspiciousDisplay(DisplayInfo {
+                flag: Some(DisplayFlag::CaptureDevice),
+                ..display("Elgato HD60")
+            }),
+            "Suspicious display detected: Elgato HD60 (capture_device)",
+        ),
+        (
+            Evidence::SuspiciousDisplay(display("Elgato HD60")),
+            "Suspicious display detected: Elgato HD60",
+        ),
+        (
+            Evidence::Window {
+                title: "Google Search - Chrome".to_string(),
+            },
+            "Forbidden Active Window detected: Google Search - Chrome",
+        ),
+        (Evidence::VirtualMachine, "Virtual Machine Detected"),
+        (
+            Evidence::ProbeFailure {
+                kind: "permission_denied".to_string(),
+                message: "permission denied reading /proc/1/exe".to_string(),
+            },
+            "Required check failed (permission_denied): permission denied reading /proc/1/exe",
+        ),
+        (
+            Evidence::Capability {
+                probe: Probe::Vm,
+                support: Support::Partial,
+                required: Support::Supported,
+            },
+            "Required check unavailable: vm is partial on this platform, supported is required",
+        ),
+    ];
+    for (evidence, text) in cases {
+        assert_eq!(violation(evidence).to_string(), text);
+    }
+}
@@ -0,0 +1,109 @@
+use core::{Config, PolicyEngine};
+use platform_common::{ExtensionInfo, ProcessInfo};
+
+const RULES: &str = r#"
+  rules:
+    processes:
+      - id: "process.editor"
+        keywords: ["code"]
+    extensions:
+      - id: "extension.writing"
+        keywords: ["grammarly"]
+    windows:
+      - id: "window.copilot"
+        keywords: ["copilot"]
+"#;
+
+fn engine(whitelist: &str) -> PolicyEngine {
+    let yaml = format!("policy:\n  whitelist:\n{}{}", whitelist, RULES);
+    let mut engine = PolicyEngine::from_policy(&Config::parse(&yaml).unwrap().policy.unwrap());
+    engine.set_explain(true);
+    engine
+}
+
+fn process(name: &str) -> ProcessInfo {
+    ProcessInfo {
+        pid: 7,
+        name: name.to_string(),
+        ..Default::default()
+    }
+}
+
+fn extension(id: &str) -> ExtensionInfo {
+    ExtensionInfo {
+        name: "Grammarly".to_string(),
+        id: id.to_string(),
+        is_enabled: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn bare_entry_matches_every_field() {
+    let engine = engine("    - \"code\"\n");
+    assert_eq!(engine.whitelist()[0].id, "code");
+
+    let processes = engine.decide(engine.check_processes(&[process("code")]));
+    assert!(processes.blocking.is_empty());
+    assert_eq!(processes.suppressed[0].allow_rule_id, "code");
+
+    let window = engine.decide(engine.check_active_window("Copilot - Visual Studio Code").into_iter().collect());
+    assert!(window.blocking.is_empty());
+    assert_eq!(window.suppressed[0].allow_rule_id, "code");
+
+    // Word-matched, like rule keywords: "codec" is not "code".
+    let window = engine.decide(engine.check_active_window("Copilot - codec settings").into_iter().collect());
+    assert_eq!(window.blocking.len(), 1);
+    assert!(window.suppressed.is_empty());
+}
+
+#[test]
+fn extension_ids_match_exactly() {
+    let engine = engine("    - id: \"grammarly-approved\"\n      extension_id: \"kbfnbcaeplbcioakkpcpgfkobkghlhen\"\n");
+    let extensions = [
+        extension("kbfnbcaeplbcioakkpcpgfkobkghlhen"),
+        extension("KBFNBCAEPLBCIOAKKPCPGFKOBKGHLHEN"),
+        extension("xkbfnbcaeplbcioakkpcpgfkobkghlhen"),
+        extension("kbfnbcaeplbcioakkpcpgfkobkghlhe"),
+    ];
+    let verdict = engine.evaluate(&[], &extensions, &[], &[], &[], &[], "", false);
+    let suppressed: Vec<String> = verdict.suppressed.iter().map(|s| s.group.subject()).collect();
+    let blocking: Vec<String> = verdict.blocking.iter().map(|g| g.subject()).collect();
+    assert_eq!(
+        suppressed,
+        ["extension:kbfnbcaeplbcioakkpcpgfkobkghlhen", "extension:KBFNBCAEPLBCIOAKKPCPGFKOBKGHLHEN"]
+    );
+    assert_eq!(
+        blocking,
+        ["extension:xkbfnbcaeplbcioakkpcpgfkobkghlhen", "extension:kbfnbcaeplbcioakkpcpgfkobkghlhe"]
+    );
+}
+
+#[test]
+fn suppressions_record_the_entry_and_the_field_it_matched() {
+    let engine = engine("    - id: \"vscode\"\n      process_name: \"code\"\n      window_title: \"visual studio code\"\n");
+    let mut violations = engine.check_processes(&[process("code")]);
+    violations.extend(engine.check_active_window("Copilot - Visual Studio Code"));
+    let verdict = engine.decide(violations);
+    assert!(verdict.blocking.is_empty() && verdict.audited.is_empty());
+    assert_eq!(verdict.risk.score, 0);
+
+    let recorded: Vec<(&str, &str, &str)> = verdict
+        .suppressed
+        .iter()
+        .map(|s| {
+            let allowed_by = s.allowed_by.as_ref().unwrap();
+            (s.allow_rule_id.as_str(), s.group.violation.rule_id.as_str(), allowed_by.field.as_str())
+        })
+        .collect();
+    assert_eq!(
+        recorded,
+        [("vscode", "process.editor", "name"), ("vscode", "window.copilot", "title")]
+    );
+    assert_eq!(verdict.suppressed[1].allowed_by.as_ref().unwrap().matcher, "word:visual studio code");
+
+    // The audit trail keeps suppressed violations in the report.
+    let report = serde_yaml::to_value(&verdict).unwrap();
+    assert_eq!(report["suppressed"][0]["allow_rule_id"], "vscode");
+    assert_eq!(report["suppressed"][0]["allowed_by"]["field"], "name");
+}
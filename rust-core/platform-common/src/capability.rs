@@ -0,0 +1,74 @@
+use crate::Probe;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// How much of a probe a platform implements, from least to most.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Support {
+    /// Always fails with `ProbeError::Unsupported`; an empty result from it
+    /// means nothing.
+    #[default]
+    Unsupported,
+    /// Runs, but misses items or fields the probe is meant to report.
+    Partial,
+    Supported,
+}
+
+impl Support {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Support::Unsupported => "unsupported",
+            Support::Partial => "partial",
+            Support::Supported => "supported",
+        }
+    }
+}
+
+impl fmt::Display for Support {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// What a profiler or scanner declares about each of its probes. Probes
+/// that are not listed are unsupported.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Capabilities(BTreeMap<Probe, Support>);
+
+impl Capabilities {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, probe: Probe, support: Support) -> Self {
+        self.0.insert(probe, support);
+        self
+    }
+
+    pub fn support(&self, probe: Probe) -> Support {
+        self.0.get(&probe).copied().unwrap_or_default()
+    }
+
+    /// Adds the declarations of `other`, e.g. a scanner's to a profiler's.
+    pub fn merge(mut self, other: Capabilities) -> Self {
+        self.0.extend(other.0);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Probe, Support)> + '_ {
+        self.0.iter().map(|(probe, support)| (*probe, *support))
+    }
+}
+
+impl FromIterator<(Probe, Support)> for Capabilities {
+    fn from_iter<I: IntoIterator<Item = (Probe, Support)>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
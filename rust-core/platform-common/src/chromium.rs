@@ -0,0 +1,215 @@
+use crate::{BrowserFamily, ExtensionInfo, InstallSource, ProbeError};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// Locales tried for `__MSG_*__` names after the manifest's
+/// `default_locale`.
+const FALLBACK_LOCALES: [&str; 2] = ["en", "en_US"];
+
+/// `Default` and every `Profile N` of a Chromium user data dir (Chrome,
+/// Edge, Brave, ...) that holds a `Preferences` file, in that order. Guest
+/// and system profiles are left out.
+pub fn chromium_profiles(user_data: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(user_data) else {
+        return Vec::new();
+    };
+    let mut profiles: Vec<(u32, PathBuf)> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let order = if name == "Default" {
+                0
+            } else {
+                name.strip_prefix("Profile ")?.parse::<u32>().ok()?.checked_add(1)?
+            };
+            Some((order, entry.path()))
+        })
+        .filter(|(_, path)| path.join("Preferences").is_file())
+        .collect();
+    profiles.sort();
+    profiles.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Extensions of one Chromium profile directory.
+///
+/// Reads the newest `Extensions/<id>/<version>/manifest.json` of every
+/// installed extension, plus unpacked extensions, which the profile only
+/// references by path. The enabled state and install source come from
+/// `extensions.settings` in `Preferences` and `Secure Preferences`, the
+/// latter taking precedence. Absolute paths found there are resolved under
+/// `root` (`/` on a live system).
+///
+/// A profile without an `Extensions` directory has none; an unreadable one
+/// is an error. Extensions with a missing or malformed manifest are
+/// skipped.
+pub fn read_chromium_extensions(profile: &Path, root: &Path) -> Result<Vec<ExtensionInfo>, ProbeError> {
+    let settings = extension_settings(profile);
+    let mut extensions = Vec::new();
+
+    let dir = profile.join("Extensions");
+    match fs::read_dir(&dir) {
+        Ok(entries) => {
+            let mut installed: Vec<(String, PathBuf)> = entries
+                .filter_map(Result::ok)
+                .filter(|entry| entry.path().is_dir())
+                .map(|entry| (entry.file_name().to_string_lossy().into_owned(), entry.path()))
+                .filter(|(id, _)| id != "Temp")
+                .collect();
+            installed.sort();
+            for (id, path) in installed {
+                if let Some(version_dir) = newest_version_dir(&path) {
+                    extensions.extend(read_extension(&id, &version_dir, settings.get(&id)));
+                }
+            }
+        }
+        Err(e) if e.kind() == ErrorKind::NotFound => {}
+        Err(e) => return Err(ProbeError::io(dir, e)),
+    }
+
+    for (id, setting) in &settings {
+        if install_source(setting) != InstallSource::Unpacked || extensions.iter().any(|e| &e.id == id) {
+            continue;
+        }
+        let Some(path) = setting.get("path").and_then(Value::as_str) else {
+            continue;
+        };
+        let path = Path::new(path);
+        let path = root.join(path.strip_prefix("/").unwrap_or(path));
+        extensions.extend(read_extension(id, &path, Some(setting)));
+    }
+    Ok(extensions)
+}
+
+/// `extensions.settings` of both preference files, merged per extension.
+/// Unreadable or malformed files contribute nothing.
+fn extension_settings(profile: &Path) -> BTreeMap<String, Map<String, Value>> {
+    let mut merged: BTreeMap<String, Map<String, Value>> = BTreeMap::new();
+    for file in ["Preferences", "Secure Preferences"] {
+        let Some(prefs) = fs::read(profile.join(file))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<Value>(&bytes).ok())
+        else {
+            continue;
+        };
+        let Some(settings) = prefs.pointer("/extensions/settings").and_then(Value::as_object) else {
+            continue;
+        };
+        for (id, setting) in settings {
+            if let Some(setting) = setting.as_object() {
+                merged.entry(id.clone()).or_default().extend(setting.clone());
+            }
+        }
+    }
+    merged
+}
+
+/// The version directory with the highest version, e.g. `1.10.0_0` over
+/// `1.9.2_0` while an update is pending.
+fn newest_version_dir(extension: &Path) -> Option<PathBuf> {
+    let version_key = |name: &str| -> Vec<u64> {
+        name.split(['.', '_']).map(|part| part.parse().unwrap_or(0)).collect()
+    };
+    fs::read_dir(extension)
+        .ok()?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .max_by_key(|entry| version_key(&entry.file_name().to_string_lossy()))
+        .map(|entry| entry.path())
+}
+
+fn read_extension(id: &str, dir: &Path, setting: Option<&Map<String, Value>>) -> Option<ExtensionInfo> {
+    let manifest: Value = serde_json::from_slice(&fs::read(dir.join("manifest.json")).ok()?).ok()?;
+    let default_locale = manifest["default_locale"].as_str();
+    let name = manifest["name"]
+        .as_str()
+        .and_then(|name| localize(name, dir, default_locale))
+        .unwrap_or_else(|| id.to_string());
+    let version = manifest["version"]
+        .as_str()
+        .map(str::to_string)
+        .or_else(|| Some(dir.file_name()?.to_string_lossy().into_owned()))
+        .unwrap_or_default();
+
+    let mut permissions = Vec::new();
+    let mut host_permissions = Vec::new();
+    for permission in manifest["permissions"].as_array().into_iter().flatten() {
+        match permission {
+            Value::String(p) if is_host_pattern(p) => push_unique(&mut host_permissions, p),
+            Value::String(p) => push_unique(&mut permissions, p),
+            // e.g. {"fileSystem": ["write"]}
+            Value::Object(map) => map.keys().for_each(|p| push_unique(&mut permissions, p)),
+            _ => {}
+        }
+    }
+    for pattern in manifest["host_permissions"].as_array().into_iter().flatten() {
+        if let Some(pattern) = pattern.as_str() {
+            push_unique(&mut host_permissions, pattern);
+        }
+    }
+
+    Some(ExtensionInfo {
+        name,
+        version,
+        id: id.to_string(),
+        family: BrowserFamily::Chromium,
+        is_enabled: setting.is_none_or(is_enabled),
+        install_source: setting.map_or(InstallSource::Unknown, install_source),
+        permissions,
+        host_permissions,
+    })
+}
+
+/// Resolves a `__MSG_key__` name from `_locales/<locale>/messages.json`.
+/// Keys are case-insensitive. Plain names are returned as they are.
+fn localize(name: &str, dir: &Path, default_locale: Option<&str>) -> Option<String> {
+    let Some(key) = name.strip_prefix("__MSG_").and_then(|rest| rest.strip_suffix("__")) else {
+        return Some(name.to_string());
+    };
+    default_locale.into_iter().chain(FALLBACK_LOCALES).find_map(|locale| {
+        let path = dir.join("_locales").join(locale).join("messages.json");
+        let messages: Value = serde_json::from_slice(&fs::read(path).ok()?).ok()?;
+        let (_, entry) = messages
+            .as_object()?
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))?;
+        entry["message"].as_str().map(str::to_string)
+    })
+}
+
+fn is_host_pattern(permission: &str) -> bool {
+    permission == "<all_urls>" || permission.contains("://")
+}
+
+fn push_unique(list: &mut Vec<String>, value: &str) {
+    if !list.iter().any(|v| v == value) {
+        list.push(value.to_string());
+    }
+}
+
+/// Older versions record `state` (0 = disabled); newer ones only keep
+/// `disable_reasons`, a bit mask or a list that is empty when enabled.
+fn is_enabled(setting: &Map<String, Value>) -> bool {
+    let disabled_by_state = setting.get("state").and_then(Value::as_u64) == Some(0);
+    let disabled_by_reasons = match setting.get("disable_reasons") {
+        Some(Value::Number(mask)) => mask.as_u64() != Some(0),
+        Some(Value::Array(reasons)) => !reasons.is_empty(),
+        _ => false,
+    };
+    !(disabled_by_state || disabled_by_reasons)
+}
+
+/// Chromium's `ManifestLocation` enum, stored as `location`.
+fn install_source(setting: &Map<String, Value>) -> InstallSource {
+    match setting.get("location").and_then(Value::as_u64) {
+        Some(1) => InstallSource::Store,
+        Some(2 | 3 | 6) => InstallSource::External,
+        Some(4) => InstallSource::Unpacked,
+        Some(5 | 10) => InstallSource::Component,
+        Some(7 | 9) => InstallSource::Policy,
+        Some(8) => InstallSource::CommandLine,
+        _ => InstallSource::Unknown,
+    }
+}
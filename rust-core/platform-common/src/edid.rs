@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+
+const HEADER: [u8; 8] = [0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00];
+const BLOCK_LEN: usize = 128;
+
+/// Offsets of the four 18-byte descriptors in the base block.
+const DESCRIPTORS: [usize; 4] = [54, 72, 90, 108];
+const SERIAL_TAG: u8 = 0xff;
+const NAME_TAG: u8 = 0xfc;
+
+/// Lowercase monitor-name fragments of HDMI/DisplayPort dummy plugs, which
+/// fake a second monitor for headless machines.
+const DUMMY_PLUG_NAMES: [&str; 4] = ["dummy", "headless", "ghost", "virtual display"];
+
+/// Lowercase monitor-name fragments of capture devices, which pose as a
+/// monitor to record or stream the screen.
+const CAPTURE_DEVICE_NAMES: [&str; 12] = [
+    "elgato",
+    "cam link",
+    "game capture",
+    "hd60",
+    "avermedia",
+    "live gamer",
+    "magewell",
+    "usb capture",
+    "hdmi capture",
+    "ezcap",
+    "ultrastudio",
+    "intensity",
+];
+
+/// The identifying parts of an EDID base block.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Edid {
+    /// Three-letter PNP vendor ID, e.g. `DEL` or `SAM`.
+    pub manufacturer: String,
+    pub product_code: u16,
+    /// The serial number descriptor, else the numeric serial, if any.
+    pub serial: Option<String>,
+    /// The monitor name descriptor, e.g. `DELL U2720Q`.
+    pub model: Option<String>,
+    pub width_mm: Option<u32>,
+    pub height_mm: Option<u32>,
+}
+
+/// Why a display is not an ordinary monitor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DisplayFlag {
+    /// A plug that only pretends a monitor is attached.
+    DummyPlug,
+    /// A capture card or stick recording the output.
+    CaptureDevice,
+}
+
+impl DisplayFlag {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DisplayFlag::DummyPlug => "dummy_plug",
+            DisplayFlag::CaptureDevice => "capture_device",
+        }
+    }
+}
+
+impl std::fmt::Display for DisplayFlag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Decodes the 128-byte base block of `bytes`; extension blocks are
+/// ignored. Fails on a short block, a wrong header or a bad checksum.
+pub fn parse_edid(bytes: &[u8]) -> Result<Edid, String> {
+    let Some(block) = bytes.get(..BLOCK_LEN) else {
+        return Err(format!("EDID is {} bytes, expected at least {}", bytes.len(), BLOCK_LEN));
+    };
+    if block[..8] != HEADER {
+        return Err("EDID header is missing".to_string());
+    }
+    if block.iter().fold(0u8, |sum, b| sum.wrapping_add(*b)) != 0 {
+        return Err("EDID checksum mismatch".to_string());
+    }
+
+    // Three 5-bit letters, 1 = 'A', big-endian.
+    let vendor = u16::from_be_bytes([block[8], block[9]]);
+    let manufacturer = [10, 5, 0]
+        .iter()
+        .map(|shift| char::from(b'A' - 1 + ((vendor >> shift) & 0x1f) as u8))
+        .collect();
+
+    let mut model = None;
+    let mut serial_text = None;
+    for offset in DESCRIPTORS {
+        let descriptor = &block[offset..offset + 18];
+        // Display descriptors start with a zero pixel clock.
+        if descriptor[..2] != [0, 0] {
+            continue;
+        }
+        match descriptor[3] {
+            NAME_TAG => model = descriptor_text(descriptor),
+            SERIAL_TAG => serial_text = descriptor_text(descriptor),
+            _ => {}
+        }
+    }
+    let serial_number = u32::from_le_bytes([block[12], block[13], block[14], block[15]]);
+
+    let (width_mm, height_mm) = timing_size(&block[DESCRIPTORS[0]..DESCRIPTORS[0] + 18])
+        .unwrap_or((u32::from(block[21]) * 10, u32::from(block[22]) * 10));
+
+    Ok(Edid {
+        manufacturer,
+        product_code: u16::from_le_bytes([block[10], block[11]]),
+        serial: serial_text.or_else(|| (serial_number != 0).then(|| serial_number.to_string())),
+        model,
+        width_mm: (width_mm != 0).then_some(width_mm),
+        height_mm: (height_mm != 0).then_some(height_mm),
+    })
+}
+
+impl Edid {
+    /// Flags known dummy plugs and capture devices by their monitor name.
+    pub fn flag(&self) -> Option<DisplayFlag> {
+        let model = self.model.as_deref()?.to_lowercase();
+        if DUMMY_PLUG_NAMES.iter().any(|name| model.contains(name)) {
+            Some(DisplayFlag::DummyPlug)
+        } else if CAPTURE_DEVICE_NAMES.iter().any(|name| model.contains(name)) {
+            Some(DisplayFlag::CaptureDevice)
+        } else {
+            None
+        }
+    }
+}
+
+/// Up to 13 characters, ended by a newline and padded with spaces.
+fn descriptor_text(descriptor: &[u8]) -> Option<String> {
+    let text = &descriptor[5..18];
+    let end = text.iter().position(|&b| b == b'\n').unwrap_or(text.len());
+    let text = String::from_utf8_lossy(&text[..end]).trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// The image size of the preferred detailed timing, in millimetres, which is
+/// more precise than the centimetre size in the header.
+fn timing_size(timing: &[u8]) -> Option<(u32, u32)> {
+    if timing[..2] == [0, 0] {
+        return None;
+    }
+    let width = u32::from(timing[12]) | (u32::from(timing[14] >> 4) << 8);
+    let height = u32::from(timing[13]) | (u32::from(timing[14] & 0x0f) << 8);
+    (width != 0 && height != 0).then_some((width, height))
+}
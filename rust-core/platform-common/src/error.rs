@@ -0,0 +1,118 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Why a `SystemProfiler` or `ProcessScanner` probe produced no result.
+///
+/// The variants separate what policy may want to treat differently: a
+/// probe the platform does not have, one the agent lacks the rights for,
+/// and one that broke.
+#[derive(Debug)]
+pub enum ProbeError {
+    /// The platform has no implementation of the probe.
+    Unsupported {
+        probe: &'static str,
+        platform: &'static str,
+    },
+    PermissionDenied {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    /// A file was read but its contents could not be understood.
+    Parse {
+        path: PathBuf,
+        source: Box<dyn Error + Send + Sync>,
+    },
+    /// None of the sources the probe relies on could be read.
+    Unavailable { message: String },
+    TimedOut { probe: &'static str, after: Duration },
+    /// A failure read back from a recorded `ProbeStatus`, e.g. by a
+    /// fixture; `kind` is the kind it was recorded with.
+    Recorded { kind: String, message: String },
+    /// An operating system API failed, e.g. a WMI query.
+    Os {
+        message: String,
+        source: Option<Box<dyn Error + Send + Sync>>,
+    },
+}
+
+impl ProbeError {
+    /// A failed read of `path`; permission errors get their own variant.
+    pub fn io(path: impl Into<PathBuf>, source: io::Error) -> Self {
+        let path = path.into();
+        if source.kind() == io::ErrorKind::PermissionDenied {
+            ProbeError::PermissionDenied { path, source }
+        } else {
+            ProbeError::Io { path, source }
+        }
+    }
+
+    pub fn parse(path: impl Into<PathBuf>, source: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+        ProbeError::Parse {
+            path: path.into(),
+            source: source.into(),
+        }
+    }
+
+    pub fn os(message: impl Into<String>, source: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+        ProbeError::Os {
+            message: message.into(),
+            source: Some(source.into()),
+        }
+    }
+
+    pub fn is_unsupported(&self) -> bool {
+        match self {
+            ProbeError::Unsupported { .. } => true,
+            ProbeError::Recorded { kind, .. } => kind == "unsupported",
+            _ => false,
+        }
+    }
+
+    /// The variant as a snake_case word, for reports.
+    pub fn kind(&self) -> &str {
+        match self {
+            ProbeError::Unsupported { .. } => "unsupported",
+            ProbeError::PermissionDenied { .. } => "permission_denied",
+            ProbeError::Io { .. } => "io",
+            ProbeError::Parse { .. } => "parse",
+            ProbeError::Unavailable { .. } => "unavailable",
+            ProbeError::TimedOut { .. } => "timed_out",
+            ProbeError::Os { .. } => "os",
+            ProbeError::Recorded { kind, .. } => kind,
+        }
+    }
+}
+
+impl fmt::Display for ProbeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProbeError::Unsupported { probe, platform } => write!(f, "{} is not supported on {}", probe, platform),
+            ProbeError::PermissionDenied { path, source } => {
+                write!(f, "permission denied reading {}: {}", path.display(), source)
+            }
+            ProbeError::Io { path, source } => write!(f, "failed to read {}: {}", path.display(), source),
+            ProbeError::Parse { path, source } => write!(f, "failed to parse {}: {}", path.display(), source),
+            ProbeError::Unavailable { message } | ProbeError::Recorded { message, .. } => f.write_str(message),
+            ProbeError::TimedOut { probe, after } => write!(f, "{} timed out after {:?}", probe, after),
+            ProbeError::Os { message, source: Some(source) } => write!(f, "{}: {}", message, source),
+            ProbeError::Os { message, source: None } => f.write_str(message),
+        }
+    }
+}
+
+impl Error for ProbeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ProbeError::PermissionDenied { source, .. } | ProbeError::Io { source, .. } => Some(source),
+            ProbeError::Parse { source, .. } | ProbeError::Os { source: Some(source), .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
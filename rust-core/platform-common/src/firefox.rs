@@ -0,0 +1,117 @@
+use crate::{BrowserFamily, ExtensionInfo, InstallSource, ProbeError};
+use serde_json::Value;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// The profiles listed in `<profiles_dir>/profiles.ini`, in file order.
+/// Relative paths are resolved against `profiles_dir`, absolute ones under
+/// `root` (`/` on a live system). Profiles whose directory is gone are left
+/// out.
+pub fn firefox_profiles(profiles_dir: &Path, root: &Path) -> Vec<PathBuf> {
+    let Ok(ini) = fs::read_to_string(profiles_dir.join("profiles.ini")) else {
+        return Vec::new();
+    };
+    let mut profiles = Vec::new();
+    let mut section: Option<(Option<String>, bool)> = None;
+    let mut finish = |section: Option<(Option<String>, bool)>| {
+        if let Some((Some(path), relative)) = section {
+            let path = if relative {
+                profiles_dir.join(path)
+            } else {
+                let path = Path::new(&path);
+                root.join(path.strip_prefix("/").unwrap_or(path))
+            };
+            if path.is_dir() {
+                profiles.push(path);
+            }
+        }
+    };
+    for line in ini.lines().map(str::trim) {
+        if line.starts_with('[') {
+            finish(section.take());
+            section = line.starts_with("[Profile").then_some((None, true));
+        } else if let (Some((path, relative)), Some((key, value))) = (section.as_mut(), line.split_once('=')) {
+            match key {
+                "Path" => *path = Some(value.to_string()),
+                "IsRelative" => *relative = value != "0",
+                _ => {}
+            }
+        }
+    }
+    finish(section);
+    profiles
+}
+
+/// Add-ons of type `extension` recorded in a Firefox profile's
+/// `extensions.json`. Themes, dictionaries and language packs are left out.
+///
+/// A profile without the file has none (Firefox writes it on first start);
+/// an unreadable or malformed one is an error.
+pub fn read_firefox_extensions(profile: &Path) -> Result<Vec<ExtensionInfo>, ProbeError> {
+    let path = profile.join("extensions.json");
+    let bytes = match fs::read(&path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(ProbeError::io(path, e)),
+    };
+    let db: Value = serde_json::from_slice(&bytes).map_err(|e| ProbeError::parse(&path, e))?;
+
+    Ok(db["addons"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|addon| addon["type"].as_str() == Some("extension"))
+        .filter_map(read_addon)
+        .collect())
+}
+
+fn read_addon(addon: &Value) -> Option<ExtensionInfo> {
+    let id = addon["id"].as_str()?.to_string();
+    let name = addon["defaultLocale"]["name"]
+        .as_str()
+        .or_else(|| addon["name"].as_str())
+        .map_or_else(|| id.clone(), str::to_string);
+    let strings = |list: &Value| -> Vec<String> {
+        list.as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect()
+    };
+    // `active` already accounts for `userDisabled` and `appDisabled` (e.g.
+    // an unsigned or incompatible add-on); check both in case it is stale.
+    let is_enabled = addon["active"].as_bool().unwrap_or(true)
+        && !addon["userDisabled"].as_bool().unwrap_or(false)
+        && !addon["appDisabled"].as_bool().unwrap_or(false);
+
+    Some(ExtensionInfo {
+        name,
+        version: addon["version"].as_str().unwrap_or_default().to_string(),
+        family: BrowserFamily::Gecko,
+        is_enabled,
+        install_source: install_source(addon),
+        permissions: strings(&addon["userPermissions"]["permissions"]),
+        host_permissions: strings(&addon["userPermissions"]["origins"]),
+        id,
+    })
+}
+
+/// The add-on's install `location`, refined for profile installs by how it
+/// was installed.
+fn install_source(addon: &Value) -> InstallSource {
+    match addon["location"].as_str() {
+        Some("app-profile") => match addon["installTelemetryInfo"]["source"].as_str() {
+            Some("amo" | "disco" | "about:addons") => InstallSource::Store,
+            Some("enterprise-policy") => InstallSource::Policy,
+            _ => InstallSource::Unknown,
+        },
+        // Loaded from about:debugging until the browser restarts.
+        Some("app-temporary") => InstallSource::Unpacked,
+        Some("app-builtin" | "app-system-defaults" | "app-system-addons") => InstallSource::Component,
+        Some("app-global" | "app-system-share" | "app-system-local" | "app-system-user") => InstallSource::External,
+        Some("winreg-app-global" | "winreg-app-user") => InstallSource::External,
+        _ => InstallSource::Unknown,
+    }
+}
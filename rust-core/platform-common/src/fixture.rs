@@ -0,0 +1,247 @@
+use crate::{
+    AntivirusInfo, BrowserInfo, Capabilities, DisplayInfo, ExtensionInfo, HostsEntry, NetworkAdapterInfo, Probe,
+    ProbeError, ProbeStatus, ProcessInfo, ProcessScanner, SnapshotError, Support, SystemProfiler, SystemSnapshot,
+    VmDetection,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A machine described in JSON: a `SystemSnapshot`, so any saved snapshot
+/// is a fixture, plus changes that take effect as time passes.
+///
+/// ```json
+/// { "processes": [...], "changes": [{ "after_secs": 10, "processes": [...] }] }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Fixture {
+    #[serde(flatten)]
+    pub snapshot: SystemSnapshot,
+    /// Applied in order once `after_secs` have passed.
+    #[serde(default)]
+    pub changes: Vec<FixtureChange>,
+}
+
+/// The parts of a fixture that change at `after_secs`. Lists that are
+/// present replace the current ones; probe statuses are merged.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FixtureChange {
+    pub after_secs: u64,
+    #[serde(default)]
+    pub antivirus: Option<Vec<AntivirusInfo>>,
+    #[serde(default)]
+    pub browsers: Option<Vec<BrowserInfo>>,
+    #[serde(default)]
+    pub extensions: Option<Vec<ExtensionInfo>>,
+    #[serde(default)]
+    pub displays: Option<Vec<DisplayInfo>>,
+    #[serde(default)]
+    pub vm: Option<VmDetection>,
+    #[serde(default)]
+    pub processes: Option<Vec<ProcessInfo>>,
+    #[serde(default)]
+    pub network_adapters: Option<Vec<NetworkAdapterInfo>>,
+    #[serde(default)]
+    pub hosts: Option<Vec<HostsEntry>>,
+    #[serde(default)]
+    pub active_window: Option<String>,
+    #[serde(default)]
+    pub probes: BTreeMap<Probe, ProbeStatus>,
+}
+
+impl Fixture {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SnapshotError> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).map_err(|source| SnapshotError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        serde_json::from_slice(&bytes).map_err(|source| SnapshotError::Invalid {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// The machine `elapsed` after the fixture started.
+    pub fn state_at(&self, elapsed: Duration) -> SystemSnapshot {
+        let mut state = self.snapshot.clone();
+        for change in self.changes.iter().filter(|c| Duration::from_secs(c.after_secs) <= elapsed) {
+            change.apply(&mut state);
+        }
+        state
+    }
+}
+
+impl FixtureChange {
+    fn apply(&self, state: &mut SystemSnapshot) {
+        fn replace<T: Clone>(target: &mut T, value: &Option<T>) {
+            if let Some(value) = value {
+                *target = value.clone();
+            }
+        }
+        replace(&mut state.antivirus, &self.antivirus);
+        replace(&mut state.browsers, &self.browsers);
+        replace(&mut state.extensions, &self.extensions);
+        replace(&mut state.displays, &self.displays);
+        replace(&mut state.processes, &self.processes);
+        replace(&mut state.network_adapters, &self.network_adapters);
+        replace(&mut state.hosts, &self.hosts);
+        if self.vm.is_some() {
+            state.vm = self.vm.clone();
+        }
+        if self.active_window.is_some() {
+            state.active_window = self.active_window.clone();
+        }
+        state.probes.extend(self.probes.iter().map(|(probe, status)| (*probe, status.clone())));
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Clock {
+    Started(Instant),
+    Fixed(Duration),
+}
+
+/// Serves a `Fixture` through `SystemProfiler` and `ProcessScanner`, for
+/// tests and demos without real OS probes.
+///
+/// Time runs from `new`, so clones share the fixture's schedule. A probe
+/// whose status in the current state is not `ok` returns that failure.
+#[derive(Debug, Clone)]
+pub struct FixtureProfiler {
+    fixture: Arc<Fixture>,
+    clock: Clock,
+}
+
+/// The scanner is the profiler: one fixture answers both.
+pub type FixtureScanner = FixtureProfiler;
+
+impl FixtureProfiler {
+    pub fn new(fixture: Fixture) -> Self {
+        Self {
+            fixture: Arc::new(fixture),
+            clock: Clock::Started(Instant::now()),
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SnapshotError> {
+        Ok(Self::new(Fixture::load(path)?))
+    }
+
+    /// Stops the clock at `elapsed`, so tests can step through the changes.
+    pub fn at(mut self, elapsed: Duration) -> Self {
+        self.clock = Clock::Fixed(elapsed);
+        self
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        match self.clock {
+            Clock::Started(start) => start.elapsed(),
+            Clock::Fixed(elapsed) => elapsed,
+        }
+    }
+
+    pub fn fixture(&self) -> &Fixture {
+        &self.fixture
+    }
+
+    /// The fixture's own `capabilities` if it lists any; otherwise every
+    /// probe is supported unless its initial status says it is not.
+    fn declared(&self, probes: impl Iterator<Item = Probe>) -> Capabilities {
+        let snapshot = &self.fixture.snapshot;
+        probes
+            .map(|probe| {
+                let support = if !snapshot.capabilities.is_empty() {
+                    snapshot.capabilities.support(probe)
+                } else if snapshot.status(probe).is_some_and(ProbeStatus::is_unsupported) {
+                    Support::Unsupported
+                } else {
+                    Support::Supported
+                };
+                (probe, support)
+            })
+            .collect()
+    }
+
+    /// The fixture's state now, or the failure it scripts for `probe`.
+    fn probe<T>(&self, probe: Probe, read: impl FnOnce(SystemSnapshot) -> T) -> Result<T, ProbeError> {
+        let state = self.fixture.state_at(self.elapsed());
+        match state.status(probe).and_then(ProbeStatus::to_error) {
+            Some(error) => Err(error),
+            None => Ok(read(state)),
+        }
+    }
+}
+
+impl SystemProfiler for FixtureProfiler {
+    fn capabilities(&self) -> Capabilities {
+        self.declared(Probe::ALL.into_iter().filter(|probe| *probe != Probe::Processes))
+    }
+
+    fn list_antivirus(&self) -> Result<Vec<AntivirusInfo>, ProbeError> {
+        self.probe(Probe::Antivirus, |state| state.antivirus)
+    }
+
+    fn list_browsers(&self) -> Result<Vec<BrowserInfo>, ProbeError> {
+        self.probe(Probe::Browsers, |state| state.browsers)
+    }
+
+    /// Snapshots do not record which browser an extension belongs to, so
+    /// every extension is listed under the first browser.
+    fn list_extensions(&self, browser: &BrowserInfo) -> Result<Vec<ExtensionInfo>, ProbeError> {
+        self.probe(Probe::Extensions, |state| {
+            let first = state.browsers.first().map(|b| (&b.name, &b.install_path));
+            if first == Some((&browser.name, &browser.install_path)) {
+                state.extensions
+            } else {
+                Vec::new()
+            }
+        })
+    }
+
+    /// The recorded extensions, or the extensions failure, whatever the
+    /// browsers probe recorded.
+    fn list_all_extensions(&self) -> (Vec<ExtensionInfo>, Option<ProbeError>) {
+        match self.probe(Probe::Extensions, |state| state.extensions) {
+            Ok(extensions) => (extensions, None),
+            Err(e) => (Vec::new(), Some(e)),
+        }
+    }
+
+    fn list_displays(&self) -> Result<Vec<DisplayInfo>, ProbeError> {
+        self.probe(Probe::Displays, |state| state.displays)
+    }
+
+    fn list_network_adapters(&self) -> Result<Vec<NetworkAdapterInfo>, ProbeError> {
+        self.probe(Probe::NetworkAdapters, |state| state.network_adapters)
+    }
+
+    fn parse_hosts_file(&self) -> Result<Vec<HostsEntry>, ProbeError> {
+        self.probe(Probe::Hosts, |state| state.hosts)
+    }
+
+    fn get_active_window(&self) -> Result<String, ProbeError> {
+        self.probe(Probe::ActiveWindow, |state| state.active_window.unwrap_or_default())
+    }
+
+    fn check_vm(&self) -> Result<bool, ProbeError> {
+        Ok(self.detect_vm()?.is_vm())
+    }
+
+    fn detect_vm(&self) -> Result<VmDetection, ProbeError> {
+        self.probe(Probe::Vm, |state| state.vm.unwrap_or_default())
+    }
+}
+
+impl ProcessScanner for FixtureProfiler {
+    fn capabilities(&self) -> Capabilities {
+        self.declared([Probe::Processes].into_iter())
+    }
+
+    fn scan(&self) -> Result<Vec<ProcessInfo>, ProbeError> {
+        self.probe(Probe::Processes, |state| state.processes)
+    }
+}
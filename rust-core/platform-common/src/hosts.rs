@@ -0,0 +1,53 @@
+use crate::{HostsEntry, ProbeError};
+use std::io::ErrorKind;
+use std::net::IpAddr;
+use std::path::Path;
+
+/// Parses a hosts file in the format shared by Linux, macOS and Windows:
+/// an address followed by one or more hostnames, `#` starting a comment
+/// anywhere on the line.
+///
+/// Every hostname on a line becomes its own entry, aliases included, so a
+/// blocked domain hidden behind a harmless first name is still seen. Lines
+/// whose address is not a valid IPv4 or IPv6 address are skipped, as the
+/// resolver itself ignores them.
+pub fn parse_hosts(text: &str) -> Vec<HostsEntry> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut entries = Vec::new();
+    for line in text.lines() {
+        let data = line.split('#').next().unwrap_or_default();
+        let mut fields = data.split_whitespace();
+        let Some(ip) = fields.next() else {
+            continue;
+        };
+        if !is_address(ip) {
+            continue;
+        }
+        entries.extend(fields.map(|domain| HostsEntry {
+            ip: ip.to_string(),
+            domain: domain.to_string(),
+        }));
+    }
+    entries
+}
+
+/// Reads and parses the hosts file at `path`. A missing file has no
+/// entries; any other read error fails.
+pub fn read_hosts_file(path: &Path) -> Result<Vec<HostsEntry>, ProbeError> {
+    match std::fs::read(path) {
+        Ok(bytes) => Ok(parse_hosts(&String::from_utf8_lossy(&bytes))),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(ProbeError::io(path, e)),
+    }
+}
+
+/// An IPv4 or IPv6 address, the latter optionally with a zone
+/// (`fe80::1%eth0`).
+fn is_address(field: &str) -> bool {
+    let address = match field.split_once('%') {
+        Some((address, zone)) if !zone.is_empty() && address.contains(':') => address,
+        Some(_) => return false,
+        None => field,
+    };
+    address.parse::<IpAddr>().is_ok()
+}
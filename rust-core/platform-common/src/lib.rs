@@ -1,4 +1,38 @@
 use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+mod capability;
+mod chromium;
+mod edid;
+mod error;
+mod firefox;
+mod fixture;
+mod hosts;
+mod preflight;
+mod snapshot;
+
+pub use capability::{Capabilities, Support};
+pub use chromium::{chromium_profiles, read_chromium_extensions};
+pub use edid::{parse_edid, DisplayFlag, Edid};
+pub use error::ProbeError;
+pub use firefox::{firefox_profiles, read_firefox_extensions};
+pub use fixture::{Fixture, FixtureChange, FixtureProfiler, FixtureScanner};
+pub use hosts::{parse_hosts, read_hosts_file};
+pub use preflight::{Deadlines, Timings};
+pub use snapshot::{Probe, ProbeStatus, SnapshotError, SystemSnapshot};
+
+/// Extensions of a browser profile directory, read with the Chromium or
+/// Firefox reader depending on its layout. Directories that are neither
+/// have none.
+pub fn read_profile_extensions(profile: &Path, root: &Path) -> Result<Vec<ExtensionInfo>, ProbeError> {
+    if profile.join("Preferences").is_file() {
+        read_chromium_extensions(profile, root)
+    } else if profile.join("prefs.js").is_file() || profile.join("extensions.json").is_file() {
+        read_firefox_extensions(profile)
+    } else {
+        Ok(Vec::new())
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AntivirusInfo {
@@ -7,12 +41,94 @@ pub struct AntivirusInfo {
     pub version: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ExtensionInfo {
     pub name: String,
     pub version: String,
     pub id: String,
+    /// The engine the extension was installed into. Chromium and Gecko IDs
+    /// have different formats, so rules can target them separately.
+    #[serde(default)]
+    pub family: BrowserFamily,
     pub is_enabled: bool,
+    #[serde(default)]
+    pub install_source: InstallSource,
+    /// API permissions from the manifest, e.g. `tabs` or `debugger`.
+    #[serde(default)]
+    pub permissions: Vec<String>,
+    /// Match patterns the extension may access, e.g. `<all_urls>`. Manifest
+    /// V2 lists them under `permissions`; they are moved here.
+    #[serde(default)]
+    pub host_permissions: Vec<String>,
+}
+
+/// Browser engine family of an extension.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BrowserFamily {
+    /// Chrome, Edge, Brave, Vivaldi, ...
+    Chromium,
+    /// Firefox and its forks.
+    Gecko,
+    #[default]
+    Unknown,
+}
+
+impl BrowserFamily {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BrowserFamily::Chromium => "chromium",
+            BrowserFamily::Gecko => "gecko",
+            BrowserFamily::Unknown => "unknown",
+        }
+    }
+}
+
+impl std::fmt::Display for BrowserFamily {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How an extension got into the profile.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallSource {
+    /// Installed by the user from the browser's store.
+    Store,
+    /// Loaded from a folder in developer mode.
+    Unpacked,
+    /// Pushed by enterprise policy.
+    Policy,
+    /// Added by another program through external extension files or the
+    /// registry.
+    External,
+    /// Shipped with the browser.
+    Component,
+    /// Passed with `--load-extension`.
+    CommandLine,
+    #[default]
+    Unknown,
+}
+
+impl InstallSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            InstallSource::Store => "store",
+            InstallSource::Unpacked => "unpacked",
+            InstallSource::Policy => "policy",
+            InstallSource::External => "external",
+            InstallSource::Component => "component",
+            InstallSource::CommandLine => "command_line",
+            InstallSource::Unknown => "unknown",
+        }
+    }
+}
+
+impl std::fmt::Display for InstallSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,35 +139,264 @@ pub struct BrowserInfo {
     pub profiles: Vec<String>, // Paths to profiles
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DisplayInfo {
     pub name: String,
     pub device_id: String,
     pub is_primary: bool, // Note: Difficult to determine via simple WMI PnPEntity, but we can try or default to false
+    #[serde(default)]
+    pub connector: ConnectorKind,
+    /// A built-in laptop panel rather than an attached monitor.
+    #[serde(default)]
+    pub is_internal: bool,
+    /// Whether the output is driven. A connected monitor can be switched
+    /// off and on again at any time, so disabled ones are still listed.
+    #[serde(default)]
+    pub is_enabled: bool,
+    #[serde(default)]
+    pub edid: Option<Edid>,
+    /// Set for known dummy plugs and capture devices.
+    #[serde(default)]
+    pub flag: Option<DisplayFlag>,
+}
+
+/// Physical connector of a display output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectorKind {
+    Hdmi,
+    DisplayPort,
+    /// Embedded DisplayPort, the usual laptop panel link.
+    EmbeddedDisplayPort,
+    Dvi,
+    Vga,
+    Lvds,
+    Dsi,
+    /// An output of a virtual GPU, e.g. in a VM.
+    Virtual,
+    Other,
+    #[default]
+    Unknown,
+}
+
+impl ConnectorKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ConnectorKind::Hdmi => "hdmi",
+            ConnectorKind::DisplayPort => "display_port",
+            ConnectorKind::EmbeddedDisplayPort => "embedded_display_port",
+            ConnectorKind::Dvi => "dvi",
+            ConnectorKind::Vga => "vga",
+            ConnectorKind::Lvds => "lvds",
+            ConnectorKind::Dsi => "dsi",
+            ConnectorKind::Virtual => "virtual",
+            ConnectorKind::Other => "other",
+            ConnectorKind::Unknown => "unknown",
+        }
+    }
+
+    /// Connectors that only drive panels built into the machine.
+    pub fn is_internal(self) -> bool {
+        matches!(
+            self,
+            ConnectorKind::EmbeddedDisplayPort | ConnectorKind::Lvds | ConnectorKind::Dsi
+        )
+    }
+}
+
+impl std::fmt::Display for ConnectorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One piece of evidence that the machine is virtualized.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VmSignal {
+    /// Where it was found, e.g. `dmi:sys_vendor` or `cpuinfo:hypervisor`.
+    pub source: String,
+    /// The value that gave it away.
+    pub value: String,
+    /// How strongly this signal alone points to a VM, 0-100.
+    pub weight: u8,
+}
+
+impl VmSignal {
+    pub fn new(source: impl Into<String>, value: impl Into<String>, weight: u8) -> Self {
+        Self {
+            source: source.into(),
+            value: value.into(),
+            weight: weight.min(100),
+        }
+    }
+}
+
+/// Result of a VM check: the signals found and how confident they make us.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VmDetection {
+    /// 0-100. Signals are independent evidence, so each one removes its
+    /// share of the remaining doubt: two 50s give 75, not 100.
+    pub confidence: u8,
+    pub signals: Vec<VmSignal>,
+}
+
+impl VmDetection {
+    /// Confidence at which `is_vm` reports a VM.
+    pub const THRESHOLD: u8 = 50;
+
+    pub fn from_signals(signals: Vec<VmSignal>) -> Self {
+        let doubt = signals
+            .iter()
+            .fold(1.0, |doubt, signal| doubt * (1.0 - f64::from(signal.weight) / 100.0));
+        Self {
+            confidence: ((1.0 - doubt) * 100.0).round() as u8,
+            signals,
+        }
+    }
+
+    pub fn is_vm(&self) -> bool {
+        self.confidence >= Self::THRESHOLD
+    }
 }
 
 pub trait SystemProfiler {
-    fn list_antivirus(&self) -> Result<Vec<AntivirusInfo>, String>;
-    fn list_browsers(&self) -> Result<Vec<BrowserInfo>, String>;
-    fn list_extensions(&self, browser: &BrowserInfo) -> Result<Vec<ExtensionInfo>, String>;
-    fn list_displays(&self) -> Result<Vec<DisplayInfo>, String>;
-    fn list_network_adapters(&self) -> Result<Vec<NetworkAdapterInfo>, String>;
-    fn parse_hosts_file(&self) -> Result<Vec<HostsEntry>, String>;
-    fn get_active_window(&self) -> Result<String, String>;
-    fn check_vm(&self) -> Result<bool, String>;
+    /// How much of each probe this profiler implements. Covers every probe
+    /// but `Probe::Processes`, which belongs to the `ProcessScanner`.
+    fn capabilities(&self) -> Capabilities;
+
+    fn list_antivirus(&self) -> Result<Vec<AntivirusInfo>, ProbeError>;
+    fn list_browsers(&self) -> Result<Vec<BrowserInfo>, ProbeError>;
+    fn list_extensions(&self, browser: &BrowserInfo) -> Result<Vec<ExtensionInfo>, ProbeError>;
+    fn list_displays(&self) -> Result<Vec<DisplayInfo>, ProbeError>;
+    fn list_network_adapters(&self) -> Result<Vec<NetworkAdapterInfo>, ProbeError>;
+    fn parse_hosts_file(&self) -> Result<Vec<HostsEntry>, ProbeError>;
+    fn get_active_window(&self) -> Result<String, ProbeError>;
+    fn check_vm(&self) -> Result<bool, ProbeError>;
+
+    /// The extensions of every browser `list_browsers` finds, with the first
+    /// error met on the way. One unreadable profile does not hide the
+    /// extensions of the others.
+    fn list_all_extensions(&self) -> (Vec<ExtensionInfo>, Option<ProbeError>) {
+        let browsers = match self.list_browsers() {
+            Ok(browsers) => browsers,
+            Err(e) => return (Vec::new(), Some(e)),
+        };
+        let mut extensions = Vec::new();
+        let mut error = None;
+        for browser in &browsers {
+            match self.list_extensions(browser) {
+                Ok(found) => extensions.extend(found),
+                Err(e) => {
+                    error.get_or_insert(e);
+                }
+            }
+        }
+        (extensions, error)
+    }
+
+    /// The signals behind `check_vm`. Profilers that only have a yes/no
+    /// answer report it as a single signal.
+    fn detect_vm(&self) -> Result<VmDetection, ProbeError> {
+        Ok(if self.check_vm()? {
+            VmDetection::from_signals(vec![VmSignal::new("check_vm", "true", 100)])
+        } else {
+            VmDetection::default()
+        })
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A running process. Everything past `name` is optional: scanners fill in
+/// what their platform exposes and leave the rest `None`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ProcessInfo {
     pub pid: u32,
     pub name: String,
+    #[serde(default)]
+    pub exe_path: Option<String>,
+    /// Full command line, arguments separated by spaces.
+    #[serde(default)]
+    pub cmdline: Option<String>,
+    #[serde(default)]
+    pub ppid: Option<u32>,
+    #[serde(default)]
+    pub uid: Option<u32>,
+    #[serde(default)]
+    pub user: Option<String>,
+    /// Unix seconds. Together with `pid` this identifies a process even
+    /// after its pid is reused.
+    #[serde(default)]
+    pub start_time: Option<u64>,
+    /// Start time in the platform's own clock (Linux: clock ticks after
+    /// boot). Finer than `start_time`, so it tells a reused pid apart even
+    /// within the same second.
+    #[serde(default)]
+    pub start_ticks: Option<u64>,
+    /// Login session (Windows session id, Linux session leader pid).
+    #[serde(default)]
+    pub session_id: Option<u32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct NetworkAdapterInfo {
     pub name: String,
     pub description: String,
     pub mac_address: String,
+    #[serde(default)]
+    pub kind: AdapterKind,
+    /// Whether the interface is administratively up and, where the platform
+    /// tells, has a link.
+    #[serde(default)]
+    pub is_up: bool,
+}
+
+/// What a network interface is, as reported by the OS rather than guessed
+/// from its name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdapterKind {
+    Ethernet,
+    Wireless,
+    Loopback,
+    /// Layer 3 tunnel (tun), as used by OpenVPN and most VPN clients.
+    Tun,
+    /// Layer 2 tunnel (tap).
+    Tap,
+    #[serde(rename = "wireguard")]
+    WireGuard,
+    Bridge,
+    /// One end of a virtual ethernet pair, e.g. a container's link.
+    Veth,
+    Other,
+    #[default]
+    Unknown,
+}
+
+impl AdapterKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AdapterKind::Ethernet => "ethernet",
+            AdapterKind::Wireless => "wireless",
+            AdapterKind::Loopback => "loopback",
+            AdapterKind::Tun => "tun",
+            AdapterKind::Tap => "tap",
+            AdapterKind::WireGuard => "wireguard",
+            AdapterKind::Bridge => "bridge",
+            AdapterKind::Veth => "veth",
+            AdapterKind::Other => "other",
+            AdapterKind::Unknown => "unknown",
+        }
+    }
+
+    /// Tunnels carry traffic somewhere other than the local network.
+    pub fn is_tunnel(self) -> bool {
+        matches!(self, AdapterKind::Tun | AdapterKind::Tap | AdapterKind::WireGuard)
+    }
+}
+
+impl std::fmt::Display for AdapterKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -61,9 +406,63 @@ pub struct HostsEntry {
 }
 
 pub trait ProcessScanner {
-    fn scan(&self) -> Result<Vec<ProcessInfo>, String>;
+    /// Declares `Probe::Processes`.
+    fn capabilities(&self) -> Capabilities;
+
+    fn scan(&self) -> Result<Vec<ProcessInfo>, ProbeError>;
+}
+
+/// Outcome of `Blocker::block`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockResult {
+    /// Exited after being asked to.
+    Terminated,
+    /// Ignored the request within the grace period and was killed.
+    Killed,
+    /// Gone before it could be stopped.
+    AlreadyExited,
+    /// The pid now belongs to a different process, which was left alone.
+    PidReused,
+    PermissionDenied,
+    /// Stopping processes is not implemented on this platform.
+    Unsupported,
+    Failed(String),
+}
+
+impl BlockResult {
+    /// Whether the offending process is no longer running.
+    pub fn is_gone(&self) -> bool {
+        matches!(
+            self,
+            BlockResult::Terminated | BlockResult::Killed | BlockResult::AlreadyExited | BlockResult::PidReused
+        )
+    }
 }
 
 pub trait Blocker {
-    fn block(&self, pid: u32) -> bool;
+    /// Stops `process`, a process observed by a `ProcessScanner`. Its pid
+    /// and start time identify it, so a pid that was reused since the scan
+    /// is not touched.
+    fn block(&self, process: &ProcessInfo) -> BlockResult;
+
+    /// Stops every process in `processes` at the same time, so the caller
+    /// waits for one grace period however many there are. Results are in
+    /// the order of `processes`.
+    fn block_all(&self, processes: &[&ProcessInfo]) -> Vec<BlockResult>
+    where
+        Self: Sync,
+    {
+        std::thread::scope(|scope| {
+            let stopping: Vec<_> = processes.iter().map(|process| scope.spawn(move || self.block(process))).collect();
+            stopping
+                .into_iter()
+                .map(|stopping| {
+                    stopping
+                        .join()
+                        .unwrap_or_else(|_| BlockResult::Failed("stopping the process panicked".to_string()))
+                })
+                .collect()
+        })
+    }
 }
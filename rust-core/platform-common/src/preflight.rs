@@ -0,0 +1,201 @@
+use crate::{
+    AntivirusInfo, BrowserInfo, DisplayInfo, ExtensionInfo, HostsEntry, NetworkAdapterInfo, Probe, ProbeError,
+    ProbeStatus, ProcessInfo, ProcessScanner, SystemProfiler, SystemSnapshot, VmDetection,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn default_timeout_ms() -> u64 {
+    10_000
+}
+
+/// How long each pre-flight probe may run, e.g.
+/// `{ timeout_ms: 5000, probe_timeouts_ms: { extensions: 15000 } }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Deadlines {
+    /// For probes without their own entry.
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    #[serde(default)]
+    pub probe_timeouts_ms: BTreeMap<Probe, u64>,
+}
+
+impl Default for Deadlines {
+    fn default() -> Self {
+        Self {
+            timeout_ms: default_timeout_ms(),
+            probe_timeouts_ms: BTreeMap::new(),
+        }
+    }
+}
+
+impl Deadlines {
+    /// Every probe gets `timeout`.
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout_ms: timeout.as_millis() as u64,
+            probe_timeouts_ms: BTreeMap::new(),
+        }
+    }
+
+    pub fn with(mut self, probe: Probe, timeout: Duration) -> Self {
+        self.probe_timeouts_ms.insert(probe, timeout.as_millis() as u64);
+        self
+    }
+
+    pub fn deadline(&self, probe: Probe) -> Duration {
+        Duration::from_millis(self.probe_timeouts_ms.get(&probe).copied().unwrap_or(self.timeout_ms))
+    }
+}
+
+/// How long the pre-flight took, in milliseconds. A probe that timed out
+/// is counted up to its deadline.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Timings {
+    pub total_ms: u64,
+    #[serde(default)]
+    pub probes: BTreeMap<Probe, u64>,
+}
+
+fn millis(duration: Duration) -> u64 {
+    duration.as_millis() as u64
+}
+
+/// The result of one probe, before it is stored in a snapshot.
+pub(crate) enum Found {
+    Antivirus(Result<Vec<AntivirusInfo>, ProbeError>),
+    Browsers(Result<Vec<BrowserInfo>, ProbeError>),
+    /// Extensions of the browsers that could be read, and the first error.
+    Extensions(Vec<ExtensionInfo>, Option<ProbeError>),
+    Displays(Result<Vec<DisplayInfo>, ProbeError>),
+    Vm(Result<VmDetection, ProbeError>),
+    Processes(Result<Vec<ProcessInfo>, ProbeError>),
+    NetworkAdapters(Result<Vec<NetworkAdapterInfo>, ProbeError>),
+    Hosts(Result<Vec<HostsEntry>, ProbeError>),
+    ActiveWindow(Result<String, ProbeError>),
+}
+
+/// Runs `probe`. The extensions probe lists the browsers itself, through
+/// `list_all_extensions`, so it does not have to wait for the browsers probe.
+pub(crate) fn run(probe: Probe, profiler: &dyn SystemProfiler, scanner: &dyn ProcessScanner) -> Found {
+    match probe {
+        Probe::Antivirus => Found::Antivirus(profiler.list_antivirus()),
+        Probe::Browsers => Found::Browsers(profiler.list_browsers()),
+        Probe::Extensions => {
+            let (extensions, error) = profiler.list_all_extensions();
+            Found::Extensions(extensions, error)
+        }
+        Probe::Displays => Found::Displays(profiler.list_displays()),
+        Probe::Vm => Found::Vm(profiler.detect_vm()),
+        Probe::Processes => Found::Processes(scanner.scan()),
+        Probe::NetworkAdapters => Found::NetworkAdapters(profiler.list_network_adapters()),
+        Probe::Hosts => Found::Hosts(profiler.parse_hosts_file()),
+        Probe::ActiveWindow => Found::ActiveWindow(profiler.get_active_window()),
+    }
+}
+
+impl SystemSnapshot {
+    /// Stores what a probe found and its status.
+    pub(crate) fn store(&mut self, found: Found) {
+        match found {
+            Found::Antivirus(result) => self.antivirus = self.record(Probe::Antivirus, result).unwrap_or_default(),
+            Found::Browsers(result) => self.browsers = self.record(Probe::Browsers, result).unwrap_or_default(),
+            Found::Extensions(extensions, error) => {
+                self.record(Probe::Extensions, error.map_or(Ok(()), Err));
+                self.extensions = extensions;
+            }
+            Found::Displays(result) => self.displays = self.record(Probe::Displays, result).unwrap_or_default(),
+            Found::Vm(result) => self.vm = self.record(Probe::Vm, result),
+            Found::Processes(result) => self.processes = self.record(Probe::Processes, result).unwrap_or_default(),
+            Found::NetworkAdapters(result) => {
+                self.network_adapters = self.record(Probe::NetworkAdapters, result).unwrap_or_default()
+            }
+            Found::Hosts(result) => self.hosts = self.record(Probe::Hosts, result).unwrap_or_default(),
+            Found::ActiveWindow(result) => self.active_window = self.record(Probe::ActiveWindow, result),
+        }
+    }
+
+    /// Records `probe` as having missed its deadline `after`.
+    fn time_out(&mut self, probe: Probe, after: Duration) {
+        self.timings.probes.insert(probe, millis(after));
+        self.probes.insert(
+            probe,
+            ProbeStatus::from(&ProbeError::TimedOut {
+                probe: probe.as_str(),
+                after,
+            }),
+        );
+    }
+
+    /// Runs every probe on its own thread and waits for each until its
+    /// deadline. A probe that misses it is recorded as timed out and left
+    /// running in the background; whatever it returns later is dropped.
+    pub fn capture_within(
+        profiler: Arc<dyn SystemProfiler + Send + Sync>,
+        scanner: Arc<dyn ProcessScanner + Send + Sync>,
+        agent_version: &str,
+        deadlines: &Deadlines,
+    ) -> Self {
+        let started = Instant::now();
+        let mut snapshot = Self::new(agent_version);
+
+        let (sender, receiver) = mpsc::channel();
+        for probe in Probe::ALL {
+            let (profiler, scanner, sender) = (Arc::clone(&profiler), Arc::clone(&scanner), sender.clone());
+            thread::spawn(move || {
+                let found = run(probe, profiler.as_ref(), scanner.as_ref());
+                // The receiver is gone once the pre-flight has moved on.
+                let _ = sender.send((probe, found, started.elapsed()));
+            });
+        }
+        drop(sender);
+        // Asked while the probes run, so a slow answer delays none of them.
+        snapshot.capabilities = profiler.capabilities().merge(scanner.capabilities());
+
+        let mut pending: BTreeSet<Probe> = Probe::ALL.into_iter().collect();
+        while let Some(next) = pending.iter().map(|probe| deadlines.deadline(*probe)).min() {
+            match receiver.recv_timeout(next.saturating_sub(started.elapsed())) {
+                Ok((probe, found, took)) => {
+                    // Already recorded as timed out.
+                    if !pending.remove(&probe) {
+                        continue;
+                    }
+                    // Queued results are only read once the pre-flight gets
+                    // to them, which can be after the deadline.
+                    let after = deadlines.deadline(probe);
+                    if took > after {
+                        snapshot.time_out(probe, after);
+                        continue;
+                    }
+                    snapshot.timings.probes.insert(probe, millis(took));
+                    snapshot.store(found);
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    let elapsed = started.elapsed();
+                    let late: Vec<Probe> =
+                        pending.iter().copied().filter(|probe| deadlines.deadline(*probe) <= elapsed).collect();
+                    for probe in late {
+                        pending.remove(&probe);
+                        snapshot.time_out(probe, deadlines.deadline(probe));
+                    }
+                }
+                // Only when a probe thread panicked; its probe gets no result.
+                Err(RecvTimeoutError::Disconnected) => {
+                    for probe in std::mem::take(&mut pending) {
+                        let error = ProbeError::Unavailable {
+                            message: format!("the {} probe stopped without a result", probe),
+                        };
+                        snapshot.probes.insert(probe, ProbeStatus::from(&error));
+                    }
+                }
+            }
+        }
+        snapshot.timings.total_ms = millis(started.elapsed());
+        snapshot
+    }
+}
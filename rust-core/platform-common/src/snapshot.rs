@@ -0,0 +1,290 @@
+use crate::preflight::{self, Timings};
+use crate::{
+    AntivirusInfo, BrowserInfo, Capabilities, DisplayInfo, ExtensionInfo, HostsEntry, NetworkAdapterInfo, ProbeError,
+    ProcessInfo, ProcessScanner, SystemProfiler, VmDetection,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// One of the probes a snapshot is made of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Probe {
+    Antivirus,
+    Browsers,
+    Extensions,
+    Displays,
+    Vm,
+    Processes,
+    NetworkAdapters,
+    Hosts,
+    ActiveWindow,
+}
+
+impl Probe {
+    pub const ALL: [Probe; 9] = [
+        Probe::Antivirus,
+        Probe::Browsers,
+        Probe::Extensions,
+        Probe::Displays,
+        Probe::Vm,
+        Probe::Processes,
+        Probe::NetworkAdapters,
+        Probe::Hosts,
+        Probe::ActiveWindow,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Probe::Antivirus => "antivirus",
+            Probe::Browsers => "browsers",
+            Probe::Extensions => "extensions",
+            Probe::Displays => "displays",
+            Probe::Vm => "vm",
+            Probe::Processes => "processes",
+            Probe::NetworkAdapters => "network_adapters",
+            Probe::Hosts => "hosts",
+            Probe::ActiveWindow => "active_window",
+        }
+    }
+}
+
+impl fmt::Display for Probe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How a probe went. A `ProbeError` is reduced to its kind and message so
+/// the status survives a round trip through JSON.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ProbeStatus {
+    Ok,
+    /// The platform has no implementation of the probe.
+    Unsupported { message: String },
+    /// The probe ran and failed; `kind` is `ProbeError::kind`.
+    Failed { kind: String, message: String },
+    /// The probe did not finish within its deadline and was left behind.
+    TimedOut { after_ms: u64, message: String },
+}
+
+impl ProbeStatus {
+    pub fn is_ok(&self) -> bool {
+        matches!(self, ProbeStatus::Ok)
+    }
+
+    pub fn is_unsupported(&self) -> bool {
+        matches!(self, ProbeStatus::Unsupported { .. })
+    }
+
+    pub fn is_timed_out(&self) -> bool {
+        matches!(self, ProbeStatus::TimedOut { .. })
+    }
+
+    /// `ok`, `unsupported`, `timed_out` or the kind of the failure.
+    pub fn kind(&self) -> &str {
+        match self {
+            ProbeStatus::Ok => "ok",
+            ProbeStatus::Unsupported { .. } => "unsupported",
+            ProbeStatus::Failed { kind, .. } => kind,
+            ProbeStatus::TimedOut { .. } => "timed_out",
+        }
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        match self {
+            ProbeStatus::Ok => None,
+            ProbeStatus::Unsupported { message }
+            | ProbeStatus::Failed { message, .. }
+            | ProbeStatus::TimedOut { message, .. } => Some(message),
+        }
+    }
+
+    /// The error a probe that ended this way returns; `None` for `Ok`.
+    pub fn to_error(&self) -> Option<ProbeError> {
+        Some(ProbeError::Recorded {
+            kind: self.kind().to_string(),
+            message: self.message()?.to_string(),
+        })
+    }
+}
+
+impl From<&ProbeError> for ProbeStatus {
+    fn from(error: &ProbeError) -> Self {
+        if error.is_unsupported() {
+            ProbeStatus::Unsupported {
+                message: error.to_string(),
+            }
+        } else if let ProbeError::TimedOut { after, .. } = error {
+            ProbeStatus::TimedOut {
+                after_ms: after.as_millis() as u64,
+                message: error.to_string(),
+            }
+        } else {
+            ProbeStatus::Failed {
+                kind: error.kind().to_string(),
+                message: error.to_string(),
+            }
+        }
+    }
+}
+
+impl fmt::Display for ProbeStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.message() {
+            Some(message) => write!(f, "{}: {}", self.kind(), message),
+            None => f.write_str(self.kind()),
+        }
+    }
+}
+
+/// Everything the pre-flight probes found on one machine at one time.
+///
+/// A snapshot can be saved and evaluated again later, e.g. to replay a
+/// disputed session against a newer policy. The inventory of a probe that
+/// did not succeed is empty; `probes` says why.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SystemSnapshot {
+    /// Unix seconds.
+    pub taken_at: u64,
+    /// `std::env::consts::OS` of the machine, e.g. `linux` or `windows`.
+    pub platform: String,
+    pub agent_version: String,
+    #[serde(default)]
+    pub antivirus: Vec<AntivirusInfo>,
+    #[serde(default)]
+    pub browsers: Vec<BrowserInfo>,
+    /// Extensions of every browser profile.
+    #[serde(default)]
+    pub extensions: Vec<ExtensionInfo>,
+    #[serde(default)]
+    pub displays: Vec<DisplayInfo>,
+    #[serde(default)]
+    pub vm: Option<VmDetection>,
+    #[serde(default)]
+    pub processes: Vec<ProcessInfo>,
+    #[serde(default)]
+    pub network_adapters: Vec<NetworkAdapterInfo>,
+    #[serde(default)]
+    pub hosts: Vec<HostsEntry>,
+    #[serde(default)]
+    pub active_window: Option<String>,
+    /// Status of every probe that ran. A probe missing here was not run.
+    #[serde(default)]
+    pub probes: BTreeMap<Probe, ProbeStatus>,
+    /// What the profiler and scanner declared they implement. Empty in
+    /// snapshots taken before capabilities were recorded.
+    #[serde(default)]
+    pub capabilities: Capabilities,
+    /// How long the pre-flight and each of its probes took.
+    #[serde(default)]
+    pub timings: Timings,
+}
+
+impl SystemSnapshot {
+    /// An empty snapshot of this machine, taken now.
+    pub fn new(agent_version: impl Into<String>) -> Self {
+        Self {
+            taken_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            platform: std::env::consts::OS.to_string(),
+            agent_version: agent_version.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Runs every probe of `profiler` and `scanner` in turn, on this
+    /// thread and without deadlines. See `capture_within`.
+    pub fn capture(profiler: &dyn SystemProfiler, scanner: &dyn ProcessScanner, agent_version: &str) -> Self {
+        let started = Instant::now();
+        let mut snapshot = Self::new(agent_version);
+        snapshot.capabilities = profiler.capabilities().merge(scanner.capabilities());
+        for probe in Probe::ALL {
+            let probe_started = Instant::now();
+            snapshot.store(preflight::run(probe, profiler, scanner));
+            snapshot.timings.probes.insert(probe, probe_started.elapsed().as_millis() as u64);
+        }
+        snapshot.timings.total_ms = started.elapsed().as_millis() as u64;
+        snapshot
+    }
+
+    /// Stores the status of `probe` and passes its result on.
+    pub fn record<T>(&mut self, probe: Probe, result: Result<T, ProbeError>) -> Option<T> {
+        let (status, value) = match result {
+            Ok(value) => (ProbeStatus::Ok, Some(value)),
+            Err(e) => (ProbeStatus::from(&e), None),
+        };
+        self.probes.insert(probe, status);
+        value
+    }
+
+    pub fn status(&self, probe: Probe) -> Option<&ProbeStatus> {
+        self.probes.get(&probe)
+    }
+
+    /// The probes that ran and did not succeed.
+    pub fn failures(&self) -> impl Iterator<Item = (Probe, &ProbeStatus)> {
+        self.probes
+            .iter()
+            .filter(|(_, status)| !status.is_ok())
+            .map(|(probe, status)| (*probe, status))
+    }
+
+    pub fn is_vm(&self) -> bool {
+        self.vm.as_ref().is_some_and(VmDetection::is_vm)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SnapshotError> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).map_err(|source| SnapshotError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        serde_json::from_slice(&bytes).map_err(|source| SnapshotError::Invalid {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Writes the snapshot as pretty-printed JSON.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SnapshotError> {
+        let path = path.as_ref();
+        let json = serde_json::to_string_pretty(self).expect("snapshots serialize to JSON");
+        fs::write(path, json + "\n").map_err(|source| SnapshotError::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io { path: PathBuf, source: io::Error },
+    Invalid { path: PathBuf, source: serde_json::Error },
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Io { path, source } => write!(f, "failed to access {}: {}", path.display(), source),
+            SnapshotError::Invalid { path, source } => write!(f, "{} is not a valid snapshot: {}", path.display(), source),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SnapshotError::Io { source, .. } => Some(source),
+            SnapshotError::Invalid { source, .. } => Some(source),
+        }
+    }
+}
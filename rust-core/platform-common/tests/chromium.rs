@@ -0,0 +1,89 @@
+use platform_common::{chromium_profiles, read_chromium_extensions, ExtensionInfo, InstallSource};
+use std::path::{Path, PathBuf};
+
+fn fixture() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/chromium")
+}
+
+fn user_data() -> PathBuf {
+    fixture().join("User Data")
+}
+
+fn default_profile() -> Vec<ExtensionInfo> {
+    read_chromium_extensions(&user_data().join("Default"), &fixture()).unwrap()
+}
+
+fn find<'a>(extensions: &'a [ExtensionInfo], name: &str) -> &'a ExtensionInfo {
+    extensions
+        .iter()
+        .find(|e| e.name == name)
+        .unwrap_or_else(|| panic!("no extension named {}", name))
+}
+
+#[test]
+fn lists_default_and_numbered_profiles() {
+    let profiles: Vec<_> = chromium_profiles(&user_data())
+        .iter()
+        .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(profiles, ["Default", "Profile 2"]);
+}
+
+#[test]
+fn reads_installed_and_unpacked_extensions() {
+    let names: Vec<_> = default_profile().into_iter().map(|e| e.name).collect();
+    // The malformed manifest and the component extension without a
+    // directory are skipped.
+    assert_eq!(names, ["ChatGPT Sidebar", "Grammar Helper", "School Policy Tool", "Answer Bot"]);
+
+    let other = read_chromium_extensions(&user_data().join("Profile 2"), &fixture()).unwrap();
+    assert_eq!(other.len(), 1);
+    assert_eq!(other[0].name, "Dark Reader");
+    assert_eq!(other[0].install_source, InstallSource::Unknown);
+    assert!(other[0].is_enabled);
+}
+
+#[test]
+fn resolves_localized_name_of_newest_version() {
+    let extensions = default_profile();
+    let sidebar = find(&extensions, "ChatGPT Sidebar");
+    assert_eq!(sidebar.version, "1.10.0");
+    assert_eq!(sidebar.id, "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
+}
+
+#[test]
+fn reads_enabled_state_and_install_source() {
+    let extensions = default_profile();
+    let state = |name| {
+        let e = find(&extensions, name);
+        (e.is_enabled, e.install_source)
+    };
+    assert_eq!(state("ChatGPT Sidebar"), (true, InstallSource::Store));
+    assert_eq!(state("Grammar Helper"), (false, InstallSource::Store));
+    // Secure Preferences overrides the disabled state in Preferences.
+    assert_eq!(state("School Policy Tool"), (true, InstallSource::Policy));
+    assert_eq!(state("Answer Bot"), (true, InstallSource::Unpacked));
+}
+
+#[test]
+fn splits_api_and_host_permissions() {
+    let extensions = default_profile();
+    let sidebar = find(&extensions, "ChatGPT Sidebar");
+    assert_eq!(sidebar.permissions, ["storage", "tabs", "fileSystem"]);
+    assert_eq!(sidebar.host_permissions, ["<all_urls>"]);
+
+    // Manifest V2 mixes host patterns into `permissions`.
+    let grammar = find(&extensions, "Grammar Helper");
+    assert_eq!(grammar.permissions, ["tabs"]);
+    assert_eq!(grammar.host_permissions, ["https://*.google.com/*", "<all_urls>"]);
+
+    let bot = find(&extensions, "Answer Bot");
+    assert_eq!(bot.permissions, ["debugger", "scripting"]);
+}
+
+#[test]
+fn profile_without_extensions_has_none() {
+    assert!(read_chromium_extensions(&user_data().join("Guest Profile"), &fixture())
+        .unwrap()
+        .is_empty());
+}
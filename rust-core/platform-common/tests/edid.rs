@@ -0,0 +1,57 @@
+use platform_common::{parse_edid, DisplayFlag, Edid};
+use std::fs;
+
+fn blob(name: &str) -> Vec<u8> {
+    fs::read(format!("{}/tests/fixtures/edid/{}", env!("CARGO_MANIFEST_DIR"), name)).unwrap()
+}
+
+#[test]
+fn decodes_monitor_identity_and_size() {
+    let edid = parse_edid(&blob("dell-u2720q.bin")).unwrap();
+    assert_eq!(
+        edid,
+        Edid {
+            manufacturer: "DEL".to_string(),
+            product_code: 0xa0f6,
+            serial: Some("ABC1234".to_string()),
+            model: Some("DELL U2720Q".to_string()),
+            width_mm: Some(597),
+            height_mm: Some(336),
+        }
+    );
+    assert_eq!(edid.flag(), None);
+}
+
+#[test]
+fn panel_without_name_or_serial() {
+    let edid = parse_edid(&blob("laptop-panel.bin")).unwrap();
+    assert_eq!(edid.manufacturer, "BOE");
+    assert_eq!(edid.product_code, 0x0a8e);
+    assert_eq!(edid.serial, None);
+    // Only unspecified-text descriptors, which are not the model name.
+    assert_eq!(edid.model, None);
+    assert_eq!((edid.width_mm, edid.height_mm), (Some(309), Some(174)));
+}
+
+#[test]
+fn numeric_serial_is_used_without_a_serial_descriptor() {
+    let edid = parse_edid(&blob("hdmi-dummy.bin")).unwrap();
+    assert_eq!(edid.serial.as_deref(), Some("16843009"));
+    // Dummy plugs report no physical size.
+    assert_eq!((edid.width_mm, edid.height_mm), (None, None));
+}
+
+#[test]
+fn flags_dummy_plugs_and_capture_devices() {
+    assert_eq!(parse_edid(&blob("hdmi-dummy.bin")).unwrap().flag(), Some(DisplayFlag::DummyPlug));
+    assert_eq!(parse_edid(&blob("cam-link.bin")).unwrap().flag(), Some(DisplayFlag::CaptureDevice));
+}
+
+#[test]
+fn malformed_blobs_are_rejected() {
+    assert!(parse_edid(&blob("corrupt.bin")).unwrap_err().contains("checksum"));
+    assert!(parse_edid(&blob("truncated.bin")).unwrap_err().contains("64 bytes"));
+    let mut shifted = blob("dell-u2720q.bin");
+    shifted.rotate_left(1);
+    assert!(parse_edid(&shifted).unwrap_err().contains("header"));
+}
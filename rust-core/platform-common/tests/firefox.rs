@@ -0,0 +1,85 @@
+use platform_common::{
+    firefox_profiles, read_firefox_extensions, read_profile_extensions, BrowserFamily, ExtensionInfo, InstallSource,
+};
+use std::path::{Path, PathBuf};
+
+fn fixture() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/firefox")
+}
+
+fn default_release() -> Vec<ExtensionInfo> {
+    read_firefox_extensions(&fixture().join("Profiles/x7k2.default-release")).unwrap()
+}
+
+fn find<'a>(extensions: &'a [ExtensionInfo], name: &str) -> &'a ExtensionInfo {
+    extensions
+        .iter()
+        .find(|e| e.name == name)
+        .unwrap_or_else(|| panic!("no extension named {}", name))
+}
+
+#[test]
+fn lists_profiles_from_profiles_ini() {
+    let profiles: Vec<_> = firefox_profiles(&fixture(), Path::new("/"))
+        .iter()
+        .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(profiles, ["x7k2.default-release", "fresh.default"]);
+}
+
+#[test]
+fn reads_extensions_but_not_themes_or_dictionaries() {
+    let extensions = default_release();
+    let names: Vec<_> = extensions.iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(
+        names,
+        ["uBlock Origin", "ChatGPT for Firefox", "Answer Bot", "Exam Kiosk", "Unsigned Helper", "Picture-In-Picture"]
+    );
+    let ublock = find(&extensions, "uBlock Origin");
+    assert_eq!(ublock.id, "uBlock0@raymondhill.net");
+    assert_eq!(ublock.version, "1.58.0");
+    assert_eq!(ublock.family, BrowserFamily::Gecko);
+    assert_eq!(ublock.permissions, ["dns", "storage", "tabs", "webRequest", "webRequestBlocking"]);
+    assert_eq!(ublock.host_permissions, ["<all_urls>"]);
+}
+
+#[test]
+fn reads_enabled_state_and_install_source() {
+    let extensions = default_release();
+    let state = |name| {
+        let e = find(&extensions, name);
+        (e.is_enabled, e.install_source)
+    };
+    assert_eq!(state("uBlock Origin"), (true, InstallSource::Store));
+    assert_eq!(state("ChatGPT for Firefox"), (false, InstallSource::Store));
+    assert_eq!(state("Answer Bot"), (true, InstallSource::Unpacked));
+    assert_eq!(state("Exam Kiosk"), (true, InstallSource::Policy));
+    assert_eq!(state("Unsigned Helper"), (false, InstallSource::Unknown));
+    assert_eq!(state("Picture-In-Picture"), (true, InstallSource::Component));
+}
+
+#[test]
+fn profile_without_extensions_json_has_none() {
+    assert!(read_firefox_extensions(&fixture().join("Profiles/fresh.default")).unwrap().is_empty());
+}
+
+#[test]
+fn malformed_extensions_json_is_an_error() {
+    let err = read_firefox_extensions(&fixture().join("Profiles/broken.default")).unwrap_err();
+    assert!(err.to_string().contains("extensions.json"), "{}", err);
+    // The JSON error is kept as the source, not flattened into the message.
+    let source = std::error::Error::source(&err).expect("parse errors keep their source");
+    assert!(source.is::<serde_json::Error>(), "{:?}", source);
+}
+
+#[test]
+fn profile_layout_picks_the_reader() {
+    let gecko = read_profile_extensions(&fixture().join("Profiles/x7k2.default-release"), Path::new("/")).unwrap();
+    assert!(gecko.iter().all(|e| e.family == BrowserFamily::Gecko));
+
+    let chromium_fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/chromium");
+    let chromium = read_profile_extensions(&chromium_fixture.join("User Data/Profile 2"), &chromium_fixture).unwrap();
+    assert_eq!(chromium[0].family, BrowserFamily::Chromium);
+
+    assert!(read_profile_extensions(&fixture(), Path::new("/")).unwrap().is_empty());
+}
@@ -0,0 +1,124 @@
+use platform_common::{
+    ExtensionInfo, Fixture, FixtureProfiler, Probe, ProbeStatus, ProcessScanner, Support, SystemProfiler,
+    SystemSnapshot,
+};
+use std::time::Duration;
+
+const SESSION: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/fixture/session.json");
+
+fn session_at(secs: u64) -> FixtureProfiler {
+    FixtureProfiler::load(SESSION).unwrap().at(Duration::from_secs(secs))
+}
+
+fn process_names(scanner: &impl ProcessScanner) -> Vec<String> {
+    scanner.scan().unwrap().into_iter().map(|p| p.name).collect()
+}
+
+#[test]
+fn serves_the_snapshot_before_any_change() {
+    let fixture = session_at(0);
+    assert_eq!(process_names(&fixture), ["systemd", "firefox"]);
+    assert_eq!(fixture.get_active_window().unwrap(), "OLL Assessment - Firefox");
+    assert_eq!(fixture.list_displays().unwrap().len(), 1);
+    assert!(!fixture.check_vm().unwrap());
+    assert!(fixture.list_network_adapters().unwrap().is_empty());
+
+    let browsers = fixture.list_browsers().unwrap();
+    assert_eq!(
+        fixture.list_extensions(&browsers[0]).unwrap()[0].id,
+        "uBlock0@raymondhill.net"
+    );
+}
+
+#[test]
+fn changes_apply_once_their_time_has_come() {
+    assert_eq!(process_names(&session_at(9)), ["systemd", "firefox"]);
+
+    let later = session_at(10);
+    assert_eq!(process_names(&later), ["systemd", "firefox", "anydesk"]);
+    assert_eq!(later.get_active_window().unwrap(), "AnyDesk");
+    // Untouched parts carry over.
+    assert_eq!(later.list_displays().unwrap().len(), 1);
+}
+
+#[test]
+fn scripted_failures_are_returned_as_errors() {
+    let antivirus = session_at(0).list_antivirus().unwrap_err();
+    assert!(antivirus.is_unsupported());
+
+    let err = session_at(25).scan().unwrap_err();
+    assert_eq!(err.kind(), "permission_denied");
+    assert!(err.to_string().contains("/proc"), "{}", err);
+    // Earlier changes still apply to the other probes.
+    assert_eq!(session_at(25).get_active_window().unwrap(), "AnyDesk");
+}
+
+#[test]
+fn capturing_a_fixture_reproduces_its_statuses() {
+    let fixture = session_at(25);
+    let snapshot = SystemSnapshot::capture(&fixture, &fixture, "test");
+    assert!(snapshot.status(Probe::Antivirus).unwrap().is_unsupported());
+    assert_eq!(snapshot.status(Probe::Processes).unwrap().kind(), "permission_denied");
+    assert_eq!(snapshot.status(Probe::Displays), Some(&ProbeStatus::Ok));
+    assert_eq!(snapshot.extensions.len(), 1);
+    assert_eq!(snapshot.active_window.as_deref(), Some("AnyDesk"));
+    // Declared from the initial statuses: a later failure is not a missing capability.
+    assert_eq!(snapshot.capabilities.support(Probe::Antivirus), Support::Unsupported);
+    assert_eq!(snapshot.capabilities.support(Probe::Processes), Support::Supported);
+}
+
+/// A fixture with one extension and `failed` as the status of `probe`.
+fn extension_fixture(failed: Option<Probe>) -> FixtureProfiler {
+    let mut snapshot = SystemSnapshot {
+        extensions: vec![ExtensionInfo {
+            name: "uBlock Origin".to_string(),
+            id: "uBlock0@raymondhill.net".to_string(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    if let Some(probe) = failed {
+        let status = ProbeStatus::Failed {
+            kind: "io".to_string(),
+            message: "no profiles".to_string(),
+        };
+        snapshot.probes.insert(probe, status);
+    }
+    FixtureProfiler::new(Fixture {
+        snapshot,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn extensions_do_not_depend_on_the_browsers_probe() {
+    // No browsers recorded, then a failed browsers probe.
+    for failed in [None, Some(Probe::Browsers)] {
+        let fixture = extension_fixture(failed);
+        let snapshot = SystemSnapshot::capture(&fixture, &fixture, "test");
+        assert_eq!(snapshot.extensions.len(), 1, "{:?}", failed);
+        assert_eq!(
+            snapshot.status(Probe::Extensions),
+            Some(&ProbeStatus::Ok),
+            "{:?}",
+            failed
+        );
+    }
+
+    let fixture = extension_fixture(Some(Probe::Extensions));
+    let snapshot = SystemSnapshot::capture(&fixture, &fixture, "test");
+    assert!(snapshot.extensions.is_empty());
+    assert_eq!(snapshot.status(Probe::Extensions).unwrap().kind(), "io");
+}
+
+#[test]
+fn saved_snapshots_are_fixtures() {
+    let fixture = session_at(0);
+    let path = std::env::temp_dir().join(format!("fixture-{}.json", std::process::id()));
+    SystemSnapshot::capture(&fixture, &fixture, "test").save(&path).unwrap();
+    let loaded = Fixture::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(loaded.changes.is_empty());
+    assert_eq!(loaded.state_at(Duration::from_secs(3600)).processes.len(), 2);
+}
@@ -0,0 +1 @@
+user_pref("browser.startup.homepage", "about:home");
@@ -0,0 +1 @@
+user_pref("extensions.lastAppVersion", "128.0.3");
@@ -0,0 +1,43 @@
+use platform_common::{parse_hosts, read_hosts_file};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn corpus() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/hosts")
+}
+
+/// Each `<name>.hosts` file in the corpus is paired with `<name>.expected`,
+/// one `ip domain` line per entry.
+#[test]
+fn parses_fixture_corpus() {
+    let mut cases: Vec<PathBuf> = fs::read_dir(corpus())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "hosts"))
+        .collect();
+    cases.sort();
+    assert!(cases.len() >= 6, "corpus is missing cases: {:?}", cases);
+    for case in cases {
+        let entries = read_hosts_file(&case).unwrap();
+        let actual: Vec<String> = entries.iter().map(|e| format!("{} {}", e.ip, e.domain)).collect();
+        let expected = fs::read_to_string(case.with_extension("expected")).unwrap();
+        let expected: Vec<&str> = expected.lines().collect();
+        assert_eq!(actual, expected, "{}", case.display());
+    }
+}
+
+#[test]
+fn crlf_and_lf_parse_alike() {
+    let lf = parse_hosts("127.0.0.1 a.example b.example # note\n::1 c.example\n");
+    let crlf = parse_hosts("127.0.0.1 a.example b.example # note\r\n::1 c.example\r\n");
+    let domains = |entries: &[platform_common::HostsEntry]| {
+        entries.iter().map(|e| e.domain.clone()).collect::<Vec<_>>()
+    };
+    assert_eq!(domains(&lf), ["a.example", "b.example", "c.example"]);
+    assert_eq!(domains(&lf), domains(&crlf));
+}
+
+#[test]
+fn missing_file_has_no_entries() {
+    assert!(read_hosts_file(&corpus().join("does-not-exist")).unwrap().is_empty());
+}
@@ -0,0 +1,175 @@
+use platform_common::{
+    AntivirusInfo, BrowserInfo, Capabilities, Deadlines, DisplayInfo, ExtensionInfo, HostsEntry, NetworkAdapterInfo,
+    Probe, ProbeError, ProbeStatus, ProcessInfo, ProcessScanner, SystemProfiler, SystemSnapshot, VmDetection,
+};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A profiler whose VM check hangs for `hang` and whose other probes each
+/// take `work`. Asking the scanner for its capabilities takes `setup`.
+struct Slow {
+    hang: Duration,
+    work: Duration,
+    setup: Duration,
+}
+
+impl Slow {
+    fn done<T>(&self, value: T) -> Result<T, ProbeError> {
+        thread::sleep(self.work);
+        Ok(value)
+    }
+}
+
+impl SystemProfiler for Slow {
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new()
+    }
+    fn list_antivirus(&self) -> Result<Vec<AntivirusInfo>, ProbeError> {
+        self.done(Vec::new())
+    }
+    fn list_browsers(&self) -> Result<Vec<BrowserInfo>, ProbeError> {
+        self.done(Vec::new())
+    }
+    fn list_extensions(&self, _browser: &BrowserInfo) -> Result<Vec<ExtensionInfo>, ProbeError> {
+        self.done(Vec::new())
+    }
+    fn list_displays(&self) -> Result<Vec<DisplayInfo>, ProbeError> {
+        self.done(vec![DisplayInfo::default()])
+    }
+    fn list_network_adapters(&self) -> Result<Vec<NetworkAdapterInfo>, ProbeError> {
+        self.done(Vec::new())
+    }
+    fn parse_hosts_file(&self) -> Result<Vec<HostsEntry>, ProbeError> {
+        self.done(Vec::new())
+    }
+    fn get_active_window(&self) -> Result<String, ProbeError> {
+        self.done("Exam".to_string())
+    }
+    fn check_vm(&self) -> Result<bool, ProbeError> {
+        thread::sleep(self.hang);
+        Ok(false)
+    }
+    fn detect_vm(&self) -> Result<VmDetection, ProbeError> {
+        thread::sleep(self.hang);
+        Ok(VmDetection::default())
+    }
+}
+
+impl ProcessScanner for Slow {
+    fn capabilities(&self) -> Capabilities {
+        thread::sleep(self.setup);
+        Capabilities::new()
+    }
+    fn scan(&self) -> Result<Vec<ProcessInfo>, ProbeError> {
+        self.done(Vec::new())
+    }
+}
+
+fn capture(slow: Slow, deadlines: &Deadlines) -> SystemSnapshot {
+    let slow = Arc::new(slow);
+    SystemSnapshot::capture_within(slow.clone(), slow, "test", deadlines)
+}
+
+#[test]
+fn a_hung_probe_times_out_without_stalling_the_others() {
+    let slow = Slow {
+        hang: Duration::from_secs(30),
+        work: Duration::from_millis(10),
+        setup: Duration::ZERO,
+    };
+    let deadlines = Deadlines::new(Duration::from_secs(5)).with(Probe::Vm, Duration::from_millis(200));
+    let started = Instant::now();
+    let snapshot = capture(slow, &deadlines);
+    assert!(started.elapsed() < Duration::from_secs(5), "{:?}", started.elapsed());
+
+    let status = snapshot.status(Probe::Vm).unwrap();
+    assert!(status.is_timed_out(), "{}", status);
+    assert_eq!(status.kind(), "timed_out");
+    assert!(matches!(status, ProbeStatus::TimedOut { after_ms: 200, .. }));
+    assert!(snapshot.vm.is_none());
+
+    assert_eq!(snapshot.probes.len(), Probe::ALL.len());
+    assert_eq!(snapshot.failures().count(), 1);
+    assert_eq!(snapshot.displays.len(), 1);
+    assert_eq!(snapshot.active_window.as_deref(), Some("Exam"));
+}
+
+#[test]
+fn a_result_after_the_deadline_is_dropped() {
+    // The VM check finishes while the pre-flight still waits for the others.
+    let slow = Slow {
+        hang: Duration::from_millis(300),
+        work: Duration::from_millis(800),
+        setup: Duration::ZERO,
+    };
+    let deadlines = Deadlines::new(Duration::from_secs(5)).with(Probe::Vm, Duration::from_millis(100));
+    let snapshot = capture(slow, &deadlines);
+
+    let status = snapshot.status(Probe::Vm).unwrap();
+    assert!(matches!(status, ProbeStatus::TimedOut { after_ms: 100, .. }), "{}", status);
+    assert!(snapshot.vm.is_none());
+    assert_eq!(snapshot.timings.probes[&Probe::Vm], 100);
+    assert_eq!(snapshot.failures().count(), 1);
+    assert_eq!(snapshot.displays.len(), 1);
+}
+
+#[test]
+fn a_result_read_after_its_deadline_times_out() {
+    // Every probe finishes after its deadline but before the pre-flight,
+    // held up by the capabilities, starts reading results.
+    let slow = Slow {
+        hang: Duration::from_millis(150),
+        work: Duration::from_millis(150),
+        setup: Duration::from_millis(400),
+    };
+    let snapshot = capture(slow, &Deadlines::new(Duration::from_millis(100)));
+
+    for probe in Probe::ALL {
+        let status = snapshot.status(probe).unwrap();
+        assert!(matches!(status, ProbeStatus::TimedOut { after_ms: 100, .. }), "{}: {}", probe, status);
+        assert_eq!(snapshot.timings.probes[&probe], 100);
+    }
+    assert!(snapshot.vm.is_none());
+    assert!(snapshot.displays.is_empty());
+    assert!(snapshot.active_window.is_none());
+}
+
+#[test]
+fn probes_run_concurrently_and_are_timed() {
+    let slow = Slow {
+        hang: Duration::from_millis(200),
+        work: Duration::from_millis(200),
+        setup: Duration::ZERO,
+    };
+    let snapshot = capture(slow, &Deadlines::default());
+    assert_eq!(snapshot.failures().count(), 0);
+
+    // Nine probes of 200 ms each, one after another, would take 1.8 s.
+    let timings = &snapshot.timings;
+    assert!(timings.total_ms < 1500, "{:?}", timings);
+    assert_eq!(timings.probes.len(), Probe::ALL.len());
+    assert!(timings.probes.values().all(|ms| *ms >= 200), "{:?}", timings);
+    assert!(timings.total_ms >= timings.probes.values().copied().max().unwrap());
+}
+
+#[test]
+fn deadlines_default_per_probe() {
+    let deadlines: Deadlines = serde_json::from_str(r#"{ "probe_timeouts_ms": { "extensions": 20000 } }"#).unwrap();
+    assert_eq!(deadlines.deadline(Probe::Extensions), Duration::from_secs(20));
+    assert_eq!(deadlines.deadline(Probe::Vm), Duration::from_secs(10));
+
+    let err = serde_json::from_str::<Deadlines>(r#"{ "timeout": 5 }"#).unwrap_err();
+    assert!(err.to_string().contains("timeout"), "{}", err);
+}
+
+#[test]
+fn timed_out_statuses_round_trip() {
+    let status = ProbeStatus::from(&ProbeError::TimedOut {
+        probe: "vm",
+        after: Duration::from_secs(3),
+    });
+    let json = serde_json::to_value(&status).unwrap();
+    assert_eq!(json, serde_json::json!({ "status": "timed_out", "after_ms": 3000, "message": "vm timed out after 3s" }));
+    assert_eq!(serde_json::from_value::<ProbeStatus>(json).unwrap(), status);
+}
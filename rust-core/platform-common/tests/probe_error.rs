@@ -0,0 +1,43 @@
+use platform_common::ProbeError;
+use std::error::Error;
+use std::io;
+use std::time::Duration;
+
+#[test]
+fn permission_errors_get_their_own_kind() {
+    let denied = ProbeError::io("/etc/shadow", io::Error::from(io::ErrorKind::PermissionDenied));
+    assert!(matches!(denied, ProbeError::PermissionDenied { .. }));
+    assert_eq!(denied.kind(), "permission_denied");
+
+    let missing = ProbeError::io("/etc/hosts", io::Error::from(io::ErrorKind::NotFound));
+    assert_eq!(missing.kind(), "io");
+    assert!(missing.to_string().starts_with("failed to read /etc/hosts: "), "{}", missing);
+}
+
+#[test]
+fn sources_are_chained() {
+    let inner = io::Error::other("RPC server unavailable");
+    let err = ProbeError::os("WMI query failed", inner);
+    assert_eq!(err.to_string(), "WMI query failed: RPC server unavailable");
+    let source = err.source().unwrap();
+    assert_eq!(source.downcast_ref::<io::Error>().unwrap().to_string(), "RPC server unavailable");
+}
+
+#[test]
+fn unsupported_and_timed_out_have_no_source() {
+    let unsupported = ProbeError::Unsupported {
+        probe: "active window",
+        platform: "Linux",
+    };
+    assert!(unsupported.is_unsupported());
+    assert_eq!(unsupported.to_string(), "active window is not supported on Linux");
+    assert!(unsupported.source().is_none());
+
+    let timed_out = ProbeError::TimedOut {
+        probe: "VM detection",
+        after: Duration::from_secs(5),
+    };
+    assert!(!timed_out.is_unsupported());
+    assert_eq!(timed_out.kind(), "timed_out");
+    assert!(timed_out.source().is_none());
+}
@@ -0,0 +1,170 @@
+use platform_common::{
+    AntivirusInfo, BrowserInfo, Capabilities, DisplayInfo, ExtensionInfo, HostsEntry, NetworkAdapterInfo, Probe,
+    ProbeError, ProbeStatus, ProcessInfo, ProcessScanner, SnapshotError, Support, SystemProfiler, SystemSnapshot,
+};
+use std::io;
+use std::path::PathBuf;
+
+/// A profiler whose VM check and second browser's extensions fail.
+struct Stub;
+
+fn browser(name: &str) -> BrowserInfo {
+    BrowserInfo {
+        name: name.to_string(),
+        version: None,
+        install_path: format!("/usr/bin/{}", name),
+        profiles: Vec::new(),
+    }
+}
+
+impl SystemProfiler for Stub {
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new().with(Probe::Vm, Support::Partial)
+    }
+    fn list_antivirus(&self) -> Result<Vec<AntivirusInfo>, ProbeError> {
+        Err(ProbeError::Unsupported {
+            probe: "antivirus inventory",
+            platform: "test",
+        })
+    }
+    fn list_browsers(&self) -> Result<Vec<BrowserInfo>, ProbeError> {
+        Ok(vec![browser("chromium"), browser("brave"), browser("firefox")])
+    }
+    fn list_extensions(&self, browser: &BrowserInfo) -> Result<Vec<ExtensionInfo>, ProbeError> {
+        if browser.name == "brave" {
+            return Err(ProbeError::io("/home/u/.config/brave", io::Error::from(io::ErrorKind::PermissionDenied)));
+        }
+        Ok(vec![ExtensionInfo {
+            name: format!("{} helper", browser.name),
+            id: browser.name.clone(),
+            ..Default::default()
+        }])
+    }
+    fn list_displays(&self) -> Result<Vec<DisplayInfo>, ProbeError> {
+        Ok(vec![DisplayInfo {
+            name: "eDP-1".to_string(),
+            ..Default::default()
+        }])
+    }
+    fn list_network_adapters(&self) -> Result<Vec<NetworkAdapterInfo>, ProbeError> {
+        Ok(Vec::new())
+    }
+    fn parse_hosts_file(&self) -> Result<Vec<HostsEntry>, ProbeError> {
+        Ok(Vec::new())
+    }
+    fn get_active_window(&self) -> Result<String, ProbeError> {
+        Ok("Exam".to_string())
+    }
+    fn check_vm(&self) -> Result<bool, ProbeError> {
+        Err(ProbeError::Unavailable {
+            message: "no VM signal source was readable".to_string(),
+        })
+    }
+}
+
+impl ProcessScanner for Stub {
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new().with(Probe::Processes, Support::Supported)
+    }
+    fn scan(&self) -> Result<Vec<ProcessInfo>, ProbeError> {
+        Ok(vec![ProcessInfo {
+            pid: 1,
+            name: "init".to_string(),
+            ..Default::default()
+        }])
+    }
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("snapshot-{}-{}.json", std::process::id(), name))
+}
+
+#[test]
+fn capture_records_every_probe() {
+    let snapshot = SystemSnapshot::capture(&Stub, &Stub, "1.2.3");
+    assert_eq!(snapshot.agent_version, "1.2.3");
+    assert_eq!(snapshot.platform, std::env::consts::OS);
+    assert!(snapshot.taken_at > 0);
+    assert_eq!(snapshot.probes.len(), Probe::ALL.len());
+
+    assert_eq!(snapshot.status(Probe::Displays), Some(&ProbeStatus::Ok));
+    assert!(snapshot.status(Probe::Antivirus).unwrap().is_unsupported());
+    assert_eq!(snapshot.status(Probe::Vm).unwrap().kind(), "unavailable");
+    assert!(snapshot.vm.is_none());
+    assert!(!snapshot.is_vm());
+    assert_eq!(snapshot.active_window.as_deref(), Some("Exam"));
+}
+
+#[test]
+fn capture_merges_profiler_and_scanner_capabilities() {
+    let snapshot = SystemSnapshot::capture(&Stub, &Stub, "1.2.3");
+    assert_eq!(snapshot.capabilities.support(Probe::Vm), Support::Partial);
+    assert_eq!(snapshot.capabilities.support(Probe::Processes), Support::Supported);
+    // Undeclared probes are unsupported.
+    assert_eq!(snapshot.capabilities.support(Probe::Hosts), Support::Unsupported);
+
+    let json = serde_json::to_value(&snapshot.capabilities).unwrap();
+    assert_eq!(json, serde_json::json!({ "vm": "partial", "processes": "supported" }));
+}
+
+#[test]
+fn one_failing_browser_keeps_the_others_extensions() {
+    let snapshot = SystemSnapshot::capture(&Stub, &Stub, "1.2.3");
+    let ids: Vec<&str> = snapshot.extensions.iter().map(|e| e.id.as_str()).collect();
+    assert_eq!(ids, ["chromium", "firefox"]);
+    let status = snapshot.status(Probe::Extensions).unwrap();
+    assert_eq!(status.kind(), "permission_denied");
+    assert!(status.message().unwrap().contains("brave"), "{}", status);
+
+    let failed: Vec<Probe> = snapshot.failures().map(|(probe, _)| probe).collect();
+    assert_eq!(failed, [Probe::Antivirus, Probe::Extensions, Probe::Vm]);
+}
+
+#[test]
+fn snapshots_round_trip_through_a_file() {
+    let snapshot = SystemSnapshot::capture(&Stub, &Stub, "1.2.3");
+    let path = temp_path("round-trip");
+    snapshot.save(&path).unwrap();
+    let loaded = SystemSnapshot::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.taken_at, snapshot.taken_at);
+    assert_eq!(loaded.probes, snapshot.probes);
+    assert_eq!(loaded.processes.len(), 1);
+    assert_eq!(loaded.extensions.len(), 2);
+    assert_eq!(loaded.displays[0].name, "eDP-1");
+}
+
+#[test]
+fn probe_statuses_are_tagged() {
+    let status = ProbeStatus::Failed {
+        kind: "io".to_string(),
+        message: "failed to read /proc".to_string(),
+    };
+    let json = serde_json::to_value(&status).unwrap();
+    assert_eq!(json, serde_json::json!({ "status": "failed", "kind": "io", "message": "failed to read /proc" }));
+    assert_eq!(serde_json::to_value(ProbeStatus::Ok).unwrap(), serde_json::json!({ "status": "ok" }));
+}
+
+#[test]
+fn minimal_snapshot_files_load() {
+    let path = temp_path("minimal");
+    std::fs::write(&path, r#"{ "taken_at": 1700000000, "platform": "windows", "agent_version": "0.1.0" }"#).unwrap();
+    let loaded = SystemSnapshot::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.platform, "windows");
+    assert!(loaded.probes.is_empty());
+    assert_eq!(loaded.failures().count(), 0);
+}
+
+#[test]
+fn invalid_snapshot_files_are_errors() {
+    let path = temp_path("invalid");
+    std::fs::write(&path, "{ not json").unwrap();
+    let err = SystemSnapshot::load(&path).unwrap_err();
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(err, SnapshotError::Invalid { .. }), "{}", err);
+
+    let err = SystemSnapshot::load(temp_path("missing")).unwrap_err();
+    assert!(matches!(err, SnapshotError::Io { .. }), "{}", err);
+}
@@ -0,0 +1,217 @@
+use crate::LinuxScanner;
+use platform_common::{BlockResult, Blocker, ProcessInfo};
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long a process gets to exit after SIGTERM by default.
+const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(3);
+
+/// How long to wait for the kernel to tear a process down after SIGKILL.
+const KILL_TIMEOUT: Duration = Duration::from_secs(2);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Stops processes with SIGTERM, then SIGKILL once the grace period is
+/// over, and checks that they are gone.
+///
+/// Before each signal the pid's start time, in clock ticks, is compared
+/// with the one in the observed `ProcessInfo`, so a pid the kernel handed to
+/// another process in the meantime is never signalled. A process whose start
+/// time is unknown is not signalled at all. Signals go through a pidfd
+/// opened before the check, so they cannot reach a process that took over
+/// the pid after it.
+#[derive(Debug, Clone)]
+pub struct LinuxBlocker {
+    scanner: LinuxScanner,
+    grace_period: Duration,
+}
+
+impl Default for LinuxBlocker {
+    fn default() -> Self {
+        Self {
+            scanner: LinuxScanner::default(),
+            grace_period: DEFAULT_GRACE_PERIOD,
+        }
+    }
+}
+
+/// Whether the pid still refers to the observed process.
+enum Identity {
+    Same,
+    Exited,
+    Reused,
+    /// The start time of the observed or the current process is unknown.
+    Unknown,
+}
+
+impl LinuxBlocker {
+    pub fn with_grace_period(mut self, grace_period: Duration) -> Self {
+        self.grace_period = grace_period;
+        self
+    }
+
+    fn identity(&self, process: &ProcessInfo) -> Identity {
+        if !self.scanner.is_running(process.pid) {
+            return Identity::Exited;
+        }
+        let Some(current) = self.scanner.read_process(process.pid) else {
+            return Identity::Exited;
+        };
+        match (process.start_ticks, current.start_ticks) {
+            (Some(observed), Some(current)) if observed == current => Identity::Same,
+            (Some(_), Some(_)) => Identity::Reused,
+            _ => Identity::Unknown,
+        }
+    }
+
+    /// Waits until `target` is gone or `timeout` passes.
+    fn wait_for_exit(&self, target: &Target, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if target.has_exited(&self.scanner, POLL_INTERVAL) {
+                return true;
+            }
+            if Instant::now() >= deadline {
+                return false;
+            }
+        }
+    }
+}
+
+impl Blocker for LinuxBlocker {
+    fn block(&self, process: &ProcessInfo) -> BlockResult {
+        let pid = process.pid;
+        // pid 0 and anything that does not fit a pid_t would address a
+        // process group rather than one process.
+        if pid == 0 || i32::try_from(pid).is_err() {
+            return BlockResult::Failed(format!("invalid pid {}", pid));
+        }
+        // Opened before the identity check: whatever it verifies is the
+        // process the pidfd refers to.
+        let target = match Target::open(pid) {
+            Ok(target) => target,
+            Err(result) => return result,
+        };
+        match self.identity(process) {
+            Identity::Exited => return BlockResult::AlreadyExited,
+            Identity::Reused => return BlockResult::PidReused,
+            Identity::Unknown => return unknown_start_time(pid),
+            Identity::Same => {}
+        }
+
+        if let Err(result) = target.signal(libc::SIGTERM) {
+            return result;
+        }
+        if self.wait_for_exit(&target, self.grace_period) {
+            return BlockResult::Terminated;
+        }
+
+        // Without a pidfd the pid may have been freed and reused while we
+        // waited.
+        match self.identity(process) {
+            Identity::Exited | Identity::Reused => return BlockResult::Terminated,
+            Identity::Unknown => return unknown_start_time(pid),
+            Identity::Same => {}
+        }
+        match target.signal(libc::SIGKILL) {
+            Ok(()) => {}
+            Err(BlockResult::AlreadyExited) => return BlockResult::Terminated,
+            Err(result) => return result,
+        }
+        if self.wait_for_exit(&target, KILL_TIMEOUT) {
+            BlockResult::Killed
+        } else {
+            BlockResult::Failed(format!("process {} is still running after SIGKILL", pid))
+        }
+    }
+}
+
+/// The process being stopped. Signals go through a pidfd, which keeps
+/// referring to the process it was opened for even if its pid is reused.
+/// Kernels before 5.3 have no pidfds; there the pid is signalled with
+/// kill(2) and the identity check has to do.
+struct Target {
+    pid: u32,
+    pidfd: Option<OwnedFd>,
+}
+
+impl Target {
+    fn open(pid: u32) -> Result<Self, BlockResult> {
+        // SAFETY: pidfd_open(2) takes no pointers.
+        let fd = unsafe { libc::syscall(libc::SYS_pidfd_open, pid as libc::pid_t, 0) };
+        if fd >= 0 {
+            // SAFETY: the descriptor was just opened and nothing else owns it.
+            let pidfd = unsafe { OwnedFd::from_raw_fd(fd as RawFd) };
+            return Ok(Self {
+                pid,
+                pidfd: Some(pidfd),
+            });
+        }
+        let error = io::Error::last_os_error();
+        match error.raw_os_error() {
+            Some(libc::ENOSYS) => Ok(Self { pid, pidfd: None }),
+            _ => Err(failure(pid, error)),
+        }
+    }
+
+    fn signal(&self, signal: libc::c_int) -> Result<(), BlockResult> {
+        let sent = match &self.pidfd {
+            // SAFETY: with a null siginfo, pidfd_send_signal(2) sends
+            // `signal` like kill(2) would; the pidfd is open while `self` is.
+            Some(pidfd) => unsafe {
+                libc::syscall(
+                    libc::SYS_pidfd_send_signal,
+                    pidfd.as_raw_fd(),
+                    signal,
+                    std::ptr::null::<libc::siginfo_t>(),
+                    0,
+                )
+            },
+            // SAFETY: kill(2) takes no pointers; the pid was checked to be a
+            // positive pid_t, so it addresses exactly one process.
+            None => unsafe { libc::kill(self.pid as libc::pid_t, signal) }.into(),
+        };
+        if sent == 0 {
+            Ok(())
+        } else {
+            Err(failure(self.pid, io::Error::last_os_error()))
+        }
+    }
+
+    /// Whether the process has exited, waiting up to `wait` for it.
+    fn has_exited(&self, scanner: &LinuxScanner, wait: Duration) -> bool {
+        match &self.pidfd {
+            Some(pidfd) => {
+                // A pidfd becomes readable once its process has exited.
+                let mut poll = libc::pollfd {
+                    fd: pidfd.as_raw_fd(),
+                    events: libc::POLLIN,
+                    revents: 0,
+                };
+                // SAFETY: `poll` is a single valid pollfd that outlives the call.
+                unsafe { libc::poll(&mut poll, 1, wait.as_millis() as libc::c_int) > 0 }
+            }
+            None => {
+                if !scanner.is_running(self.pid) {
+                    return true;
+                }
+                thread::sleep(wait);
+                false
+            }
+        }
+    }
+}
+
+fn unknown_start_time(pid: u32) -> BlockResult {
+    BlockResult::Failed(format!("start time of process {} is unknown, not signalling it", pid))
+}
+
+fn failure(pid: u32, error: io::Error) -> BlockResult {
+    match error.raw_os_error() {
+        Some(libc::ESRCH) => BlockResult::AlreadyExited,
+        Some(libc::EPERM) => BlockResult::PermissionDenied,
+        _ => BlockResult::Failed(format!("failed to signal process {}: {}", pid, error)),
+    }
+}
@@ -0,0 +1,394 @@
+use crate::{read_attribute, LinuxProfiler};
+use platform_common::{chromium_profiles, firefox_profiles, BrowserInfo};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where native packages put their launchers, searched for a bare `Exec`.
+const BIN_DIRS: [&str; 4] = ["/usr/local/bin", "/usr/bin", "/bin", "/snap/bin"];
+
+/// How a browser was installed. Each channel keeps its profiles in a
+/// different place: snaps and flatpaks are confined to their own directory
+/// under the home.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Channel {
+    Native,
+    Snap,
+    Flatpak,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Layout {
+    /// A user data dir holding `Default` and `Profile N` directories.
+    Chromium,
+    /// A directory with `profiles.ini` listing the profiles.
+    Firefox,
+}
+
+/// A browser whose profiles we know how to find. Profile roots are
+/// relative to the home directory.
+struct Family {
+    /// Launcher names of native packages.
+    commands: &'static [&'static str],
+    snap: Option<&'static str>,
+    flatpak: Option<&'static str>,
+    native_root: &'static str,
+    snap_root: Option<&'static str>,
+    flatpak_root: Option<&'static str>,
+    layout: Layout,
+}
+
+const FAMILIES: [Family; 6] = [
+    Family {
+        commands: &["google-chrome", "google-chrome-stable", "google-chrome-beta", "google-chrome-unstable"],
+        snap: None,
+        flatpak: Some("com.google.Chrome"),
+        native_root: ".config/google-chrome",
+        snap_root: None,
+        flatpak_root: Some(".var/app/com.google.Chrome/config/google-chrome"),
+        layout: Layout::Chromium,
+    },
+    Family {
+        commands: &["chromium", "chromium-browser"],
+        snap: Some("chromium"),
+        flatpak: Some("org.chromium.Chromium"),
+        native_root: ".config/chromium",
+        snap_root: Some("snap/chromium/common/chromium"),
+        flatpak_root: Some(".var/app/org.chromium.Chromium/config/chromium"),
+        layout: Layout::Chromium,
+    },
+    Family {
+        commands: &["brave-browser", "brave-browser-stable", "brave"],
+        snap: Some("brave"),
+        flatpak: Some("com.brave.Browser"),
+        native_root: ".config/BraveSoftware/Brave-Browser",
+        snap_root: Some("snap/brave/current/.config/BraveSoftware/Brave-Browser"),
+        flatpak_root: Some(".var/app/com.brave.Browser/config/BraveSoftware/Brave-Browser"),
+        layout: Layout::Chromium,
+    },
+    Family {
+        commands: &["microsoft-edge", "microsoft-edge-stable", "microsoft-edge-beta", "microsoft-edge-dev"],
+        snap: None,
+        flatpak: Some("com.microsoft.Edge"),
+        native_root: ".config/microsoft-edge",
+        snap_root: None,
+        flatpak_root: Some(".var/app/com.microsoft.Edge/config/microsoft-edge"),
+        layout: Layout::Chromium,
+    },
+    Family {
+        commands: &["vivaldi", "vivaldi-stable"],
+        snap: None,
+        flatpak: Some("com.vivaldi.Vivaldi"),
+        native_root: ".config/vivaldi",
+        snap_root: None,
+        flatpak_root: Some(".var/app/com.vivaldi.Vivaldi/config/vivaldi"),
+        layout: Layout::Chromium,
+    },
+    Family {
+        commands: &["firefox", "firefox-esr"],
+        snap: Some("firefox"),
+        flatpak: Some("org.mozilla.firefox"),
+        native_root: ".mozilla/firefox",
+        snap_root: Some("snap/firefox/common/.mozilla/firefox"),
+        flatpak_root: Some(".var/app/org.mozilla.firefox/.mozilla/firefox"),
+        layout: Layout::Firefox,
+    },
+];
+
+impl Family {
+    fn profile_root(&self, channel: Channel) -> Option<&'static str> {
+        match channel {
+            Channel::Native => Some(self.native_root),
+            Channel::Snap => self.snap_root,
+            Channel::Flatpak => self.flatpak_root,
+        }
+    }
+}
+
+/// The `[Desktop Entry]` keys we use. Localized keys (`Name[de]`) are
+/// ignored.
+#[derive(Debug, Default)]
+struct DesktopEntry {
+    name: String,
+    exec: String,
+    categories: Vec<String>,
+    hidden: bool,
+    /// `X-Flatpak`: the app id of a flatpak export.
+    flatpak: Option<String>,
+    /// `X-SnapInstanceName`: the snap a snapd-generated entry launches.
+    snap: Option<String>,
+}
+
+fn parse_desktop_entry(text: &str) -> Option<DesktopEntry> {
+    let mut entry = DesktopEntry::default();
+    let mut in_entry = false;
+    let mut application = true;
+    for line in text.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=').filter(|_| in_entry) else {
+            continue;
+        };
+        let value = value.trim().to_string();
+        match key.trim() {
+            "Type" => application = value == "Application",
+            "Name" => entry.name = value,
+            "Exec" => entry.exec = value,
+            "Categories" => entry.categories = value.split(';').filter(|c| !c.is_empty()).map(String::from).collect(),
+            "Hidden" => entry.hidden = value == "true",
+            "X-Flatpak" => entry.flatpak = Some(value),
+            "X-SnapInstanceName" => entry.snap = Some(value),
+            _ => {}
+        }
+    }
+    (application && !entry.name.is_empty()).then_some(entry)
+}
+
+/// Splits an `Exec` value into arguments, honouring double quotes and
+/// dropping field codes such as `%U`.
+fn exec_args(exec: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut in_arg = false;
+    let mut chars = exec.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                in_arg = true;
+            }
+            '\\' if quoted => current.extend(chars.next()),
+            c if c.is_whitespace() && !quoted => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    args.retain(|arg| !(arg.len() == 2 && arg.starts_with('%')));
+    args
+}
+
+/// The program an `Exec` line runs, skipping an `env VAR=value` prefix.
+fn exec_program(exec: &str) -> Option<String> {
+    exec_args(exec)
+        .into_iter()
+        .find(|arg| arg != "env" && (arg.starts_with('/') || !arg.contains('=')))
+}
+
+fn base_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// Directories holding `.desktop` files, highest priority first: the
+/// user's, flatpak and snap exports, then `$XDG_DATA_DIRS`.
+fn application_dirs(profiler: &LinuxProfiler) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    let user_data = profiler
+        .data_home
+        .clone()
+        .or_else(|| profiler.home.as_ref().map(|home| home.join(".local/share")));
+    dirs.extend(user_data.clone());
+    dirs.extend(user_data.map(|data| data.join("flatpak/exports/share")));
+    dirs.push(PathBuf::from("/var/lib/flatpak/exports/share"));
+    dirs.push(PathBuf::from("/var/lib/snapd/desktop"));
+    dirs.extend(profiler.data_dirs.iter().cloned());
+
+    let mut seen = HashSet::new();
+    dirs.into_iter()
+        .filter(|dir| seen.insert(dir.clone()))
+        .map(|dir| dir.join("applications"))
+        .collect()
+}
+
+/// Installed browsers: every desktop entry in the WebBrowser category,
+/// plus browser snaps that have no desktop entry.
+///
+/// A desktop file id found in several directories is taken from the first
+/// one only, as the XDG spec prescribes, so a user's `Hidden=true` copy
+/// hides the system entry.
+pub(crate) fn list(profiler: &LinuxProfiler) -> Vec<BrowserInfo> {
+    let mut browsers = Vec::new();
+    let mut seen_ids = HashSet::new();
+    let mut seen_installs = HashSet::new();
+
+    for dir in application_dirs(profiler) {
+        let Ok(entries) = fs::read_dir(profiler.rooted(&dir)) else {
+            continue;
+        };
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "desktop"))
+            .collect();
+        files.sort();
+        for file in files {
+            let id = file.file_name().unwrap_or_default().to_os_string();
+            if !seen_ids.insert(id) {
+                continue;
+            }
+            let Some(entry) = fs::read_to_string(&file).ok().and_then(|text| parse_desktop_entry(&text)) else {
+                continue;
+            };
+            if entry.hidden || !entry.categories.iter().any(|c| c == "WebBrowser") {
+                continue;
+            }
+            let browser = inspect(profiler, &entry);
+            if seen_installs.insert(browser.install_path.clone()) {
+                browsers.push(browser);
+            }
+        }
+    }
+
+    for family in &FAMILIES {
+        let Some(snap) = family.snap else {
+            continue;
+        };
+        let install_path = format!("/snap/bin/{}", snap);
+        if profiler.rooted(Path::new(&install_path)).exists() && seen_installs.insert(install_path.clone()) {
+            browsers.push(describe(profiler, snap.to_string(), Channel::Snap, snap, install_path));
+        }
+    }
+    browsers
+}
+
+fn inspect(profiler: &LinuxProfiler, entry: &DesktopEntry) -> BrowserInfo {
+    let program = exec_program(&entry.exec).unwrap_or_default();
+    if let Some(app_id) = &entry.flatpak {
+        let install_path = flatpak_install(profiler, app_id);
+        return describe(profiler, entry.name.clone(), Channel::Flatpak, app_id, install_path);
+    }
+    if let Some(snap) = entry.snap.as_deref().or_else(|| program.strip_prefix("/snap/bin/")) {
+        let install_path = format!("/snap/bin/{}", snap);
+        return describe(profiler, entry.name.clone(), Channel::Snap, snap, install_path);
+    }
+    let install_path = if program.starts_with('/') {
+        program.clone()
+    } else {
+        BIN_DIRS
+            .iter()
+            .map(|dir| format!("{}/{}", dir, program))
+            .find(|path| profiler.rooted(Path::new(path)).exists())
+            .unwrap_or_else(|| program.clone())
+    };
+    describe(profiler, entry.name.clone(), Channel::Native, base_name(&program), install_path)
+}
+
+/// The flatpak's installation directory, per-user before system-wide.
+fn flatpak_install(profiler: &LinuxProfiler, app_id: &str) -> String {
+    let user = profiler
+        .home
+        .as_ref()
+        .map(|home| home.join(".local/share/flatpak/app").join(app_id));
+    let system = Path::new("/var/lib/flatpak/app").join(app_id);
+    user.filter(|path| profiler.rooted(path).exists())
+        .unwrap_or(system)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// `app` is the launcher name, snap name or flatpak id, depending on
+/// `channel`.
+fn describe(profiler: &LinuxProfiler, name: String, channel: Channel, app: &str, install_path: String) -> BrowserInfo {
+    let family = FAMILIES.iter().find(|family| match channel {
+        Channel::Native => family.commands.contains(&app),
+        Channel::Snap => family.snap == Some(app),
+        Channel::Flatpak => family.flatpak == Some(app),
+    });
+    let profile_root = family
+        .and_then(|family| Some((family, profiler.home.as_ref()?.join(family.profile_root(channel)?))))
+        .map(|(family, root)| (family.layout, profiler.rooted(&root)));
+
+    let profiles = match &profile_root {
+        Some((Layout::Chromium, root)) => chromium_profiles(root),
+        Some((Layout::Firefox, root)) => firefox_profiles(root, profiler.root()),
+        None => Vec::new(),
+    };
+    let version = match channel {
+        Channel::Snap => snap_version(profiler, app),
+        Channel::Flatpak => flatpak_version(profiler, &install_path, app),
+        Channel::Native => None,
+    }
+    .or_else(|| match &profile_root {
+        Some((Layout::Chromium, root)) => read_attribute(&root.join("Last Version")),
+        Some((Layout::Firefox, _)) => {
+            firefox_install_version(profiler, &install_path).or_else(|| firefox_profile_version(profiles.first()?))
+        }
+        None => None,
+    });
+
+    BrowserInfo {
+        name,
+        version,
+        install_path,
+        profiles: profiles
+            .iter()
+            .map(|profile| profiler.unrooted(profile).to_string_lossy().into_owned())
+            .collect(),
+    }
+}
+
+/// `version:` from the installed snap's `meta/snap.yaml`.
+fn snap_version(profiler: &LinuxProfiler, snap: &str) -> Option<String> {
+    let yaml = fs::read_to_string(profiler.rooted(&Path::new("/snap").join(snap).join("current/meta/snap.yaml"))).ok()?;
+    yaml.lines()
+        .find_map(|line| line.strip_prefix("version:"))
+        .map(|version| version.trim().trim_matches(|c| c == '\'' || c == '"').to_string())
+        .filter(|version| !version.is_empty())
+}
+
+/// The newest `<release version="...">` of the flatpak's AppStream data.
+fn flatpak_version(profiler: &LinuxProfiler, install_path: &str, app_id: &str) -> Option<String> {
+    let share = profiler.rooted(&Path::new(install_path).join("current/active/files/share"));
+    ["metainfo", "appdata"]
+        .iter()
+        .flat_map(|dir| {
+            ["metainfo.xml", "appdata.xml"]
+                .map(|suffix| share.join(dir).join(format!("{}.{}", app_id, suffix)))
+        })
+        .find_map(|path| fs::read_to_string(path).ok())
+        .and_then(|xml| {
+            let release = &xml[xml.find("<release ")?..];
+            let version = &release[release.find("version=\"")? + "version=\"".len()..];
+            Some(version[..version.find('"')?].to_string())
+        })
+}
+
+/// `Version` from the `application.ini` next to the real Firefox binary.
+/// The launcher is usually a symlink (or a wrapper script) into
+/// `/usr/lib/firefox`.
+fn firefox_install_version(profiler: &LinuxProfiler, install_path: &str) -> Option<String> {
+    let launcher = Path::new(install_path);
+    let mut dirs = Vec::new();
+    if let Ok(target) = fs::read_link(profiler.rooted(launcher)) {
+        let target = match launcher.parent() {
+            Some(parent) if target.is_relative() => parent.join(target),
+            _ => target,
+        };
+        dirs.extend(target.parent().map(Path::to_path_buf));
+    }
+    dirs.push(Path::new("/usr/lib").join(base_name(install_path)));
+    dirs.into_iter().find_map(|dir| {
+        let ini = fs::read_to_string(profiler.rooted(&dir.join("application.ini"))).ok()?;
+        ini.lines().find_map(|line| line.strip_prefix("Version=")).map(str::to_string)
+    })
+}
+
+/// `LastVersion` from a profile's `compatibility.ini`, without the build
+/// id (`128.0.3_20240730184107/20240730184107`).
+fn firefox_profile_version(profile: &Path) -> Option<String> {
+    let ini = fs::read_to_string(profile.join("compatibility.ini")).ok()?;
+    let version = ini.lines().find_map(|line| line.strip_prefix("LastVersion="))?;
+    Some(version.split('_').next().unwrap_or(version).to_string())
+}
@@ -0,0 +1,75 @@
+use crate::read_attribute;
+use platform_common::{parse_edid, ConnectorKind, DisplayInfo, ProbeError};
+use std::fs;
+use std::path::Path;
+
+/// Lists the connected outputs in `<root>/sys/class/drm`, sorted by
+/// connector (`card0-eDP-1`, `card0-HDMI-A-1`, ...).
+///
+/// A connector without a readable EDID is still listed, named after the
+/// connector; only an unreadable directory fails.
+pub(crate) fn list(root: &Path) -> Result<Vec<DisplayInfo>, ProbeError> {
+    let drm = root.join("sys/class/drm");
+    let entries = fs::read_dir(&drm).map_err(|e| ProbeError::io(&drm, e))?;
+    let mut connectors: Vec<(String, String)> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            // `card0` itself, render nodes and `version` are not connectors.
+            let (card, connector) = name.split_once('-')?;
+            card.starts_with("card").then(|| (name.clone(), connector.to_string()))
+        })
+        .collect();
+    connectors.sort();
+
+    Ok(connectors
+        .into_iter()
+        .filter(|(dir, _)| read_attribute(&drm.join(dir).join("status")).as_deref() == Some("connected"))
+        .filter_map(|(dir, connector)| read_display(&drm.join(&dir), dir, &connector))
+        .collect())
+}
+
+fn read_display(dir: &Path, device_id: String, connector: &str) -> Option<DisplayInfo> {
+    // `HDMI-A-1` is connector type `HDMI-A`, index 1.
+    let type_name = connector.rsplit_once('-').map_or(connector, |(kind, _)| kind);
+    if type_name == "Writeback" {
+        return None;
+    }
+    let kind = connector_kind(type_name);
+    let edid = fs::read(dir.join("edid")).ok().and_then(|bytes| parse_edid(&bytes).ok());
+    let name = edid
+        .as_ref()
+        .and_then(|edid| edid.model.clone())
+        .or_else(|| {
+            let edid = edid.as_ref()?;
+            Some(format!("{} {:04X}", edid.manufacturer, edid.product_code))
+        })
+        .unwrap_or_else(|| connector.to_string());
+
+    Some(DisplayInfo {
+        name,
+        device_id,
+        is_primary: false,
+        connector: kind,
+        is_internal: kind.is_internal(),
+        is_enabled: read_attribute(&dir.join("enabled")).as_deref() == Some("enabled"),
+        flag: edid.as_ref().and_then(|edid| edid.flag()),
+        edid,
+    })
+}
+
+/// Connector type names from the kernel's `drm_connector_enum_list`.
+fn connector_kind(type_name: &str) -> ConnectorKind {
+    match type_name {
+        "HDMI-A" | "HDMI-B" => ConnectorKind::Hdmi,
+        "DP" => ConnectorKind::DisplayPort,
+        "eDP" => ConnectorKind::EmbeddedDisplayPort,
+        "DVI-I" | "DVI-D" | "DVI-A" => ConnectorKind::Dvi,
+        "VGA" => ConnectorKind::Vga,
+        "LVDS" => ConnectorKind::Lvds,
+        "DSI" => ConnectorKind::Dsi,
+        "Virtual" => ConnectorKind::Virtual,
+        "Unknown" => ConnectorKind::Unknown,
+        _ => ConnectorKind::Other,
+    }
+}
@@ -1,47 +1,140 @@
 use platform_common::{
-    AntivirusInfo, Blocker, BrowserInfo, DisplayInfo, ExtensionInfo, HostsEntry, NetworkAdapterInfo,
-    ProcessInfo, ProcessScanner, SystemProfiler,
+    read_hosts_file, read_profile_extensions, AntivirusInfo, BrowserInfo, Capabilities, DisplayInfo, ExtensionInfo,
+    HostsEntry, NetworkAdapterInfo, Probe, ProbeError, Support, SystemProfiler, VmDetection,
 };
+use std::path::{Path, PathBuf};
 
-pub struct LinuxProfiler;
-impl SystemProfiler for LinuxProfiler {
-    fn list_antivirus(&self) -> Result<Vec<AntivirusInfo>, String> {
-        Ok(vec![])
-    }
-    fn list_browsers(&self) -> Result<Vec<BrowserInfo>, String> {
-        Ok(vec![])
-    }
-    fn list_extensions(&self, _browser: &BrowserInfo) -> Result<Vec<ExtensionInfo>, String> {
-        Ok(vec![])
+mod blocker;
+mod browsers;
+mod display;
+mod network;
+mod process;
+mod vm;
+
+pub use blocker::LinuxBlocker;
+pub use process::LinuxScanner;
+
+/// Inventory read from `/sys`, `/proc`, `/etc` and the user's home, all
+/// resolved under a configurable root so tests can point it at a fixture
+/// tree.
+#[derive(Debug, Clone)]
+pub struct LinuxProfiler {
+    root: PathBuf,
+    /// The student's home directory, as a path on the live system.
+    home: Option<PathBuf>,
+    /// `$XDG_DATA_HOME`; `<home>/.local/share` when unset.
+    data_home: Option<PathBuf>,
+    /// `$XDG_DATA_DIRS`.
+    data_dirs: Vec<PathBuf>,
+}
+
+impl Default for LinuxProfiler {
+    /// The live system, with the home and XDG directories of the user
+    /// running the agent.
+    fn default() -> Self {
+        let env_path = |name| std::env::var_os(name).filter(|v| !v.is_empty()).map(PathBuf::from);
+        let mut profiler = Self::with_root("/");
+        profiler.home = env_path("HOME");
+        profiler.data_home = env_path("XDG_DATA_HOME");
+        if let Some(dirs) = std::env::var_os("XDG_DATA_DIRS").filter(|v| !v.is_empty()) {
+            profiler.data_dirs = std::env::split_paths(&dirs).collect();
+        }
+        profiler
     }
-    fn list_displays(&self) -> Result<Vec<DisplayInfo>, String> {
-        Ok(vec![])
+}
+
+impl LinuxProfiler {
+    /// Reads `<root>/sys`, `<root>/proc` and so on instead of the live ones.
+    /// No home directory is set.
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            home: None,
+            data_home: None,
+            data_dirs: vec![PathBuf::from("/usr/local/share"), PathBuf::from("/usr/share")],
+        }
     }
-    fn check_vm(&self) -> Result<bool, String> {
-        Ok(false)
+
+    /// Looks for per-user browsers and profiles in `home`, a path on the
+    /// live system (e.g. `/home/student`) that is resolved under the root.
+    pub fn with_home(mut self, home: impl Into<PathBuf>) -> Self {
+        self.home = Some(home.into());
+        self
     }
-    fn list_network_adapters(&self) -> Result<Vec<NetworkAdapterInfo>, String> {
-        Ok(vec![])
+
+    pub fn root(&self) -> &Path {
+        &self.root
     }
-    fn parse_hosts_file(&self) -> Result<Vec<HostsEntry>, String> {
-        Ok(vec![])
+
+    /// `path`, as it appears on the live system, under the root.
+    pub(crate) fn rooted(&self, path: &Path) -> PathBuf {
+        self.root.join(path.strip_prefix("/").unwrap_or(path))
     }
-    fn get_active_window(&self) -> Result<String, String> {
-        Ok("Linux Window".to_string())
+
+    /// The live-system path of `path`, a path under the root.
+    pub(crate) fn unrooted(&self, path: &Path) -> PathBuf {
+        match path.strip_prefix(&self.root) {
+            Ok(relative) => Path::new("/").join(relative),
+            Err(_) => path.to_path_buf(),
+        }
     }
 }
 
-pub struct LinuxScanner;
-impl ProcessScanner for LinuxScanner {
-    fn scan(&self) -> Result<Vec<ProcessInfo>, String> {
-        Ok(vec![])
-    }
+/// A one-value `/sys` or `/proc` file, trimmed. `None` when it is missing,
+/// unreadable or empty.
+pub(crate) fn read_attribute(path: &Path) -> Option<String> {
+    let text = std::fs::read_to_string(path).ok()?;
+    Some(text.trim().to_string()).filter(|s| !s.is_empty())
 }
 
-pub struct LinuxBlocker;
-impl Blocker for LinuxBlocker {
-    fn block(&self, pid: u32) -> bool {
-        println!("Blocking process {} on Linux", pid);
-        true
+impl SystemProfiler for LinuxProfiler {
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new()
+            .with(Probe::Antivirus, Support::Unsupported)
+            .with(Probe::Browsers, Support::Supported)
+            .with(Probe::Extensions, Support::Supported)
+            .with(Probe::Displays, Support::Supported)
+            .with(Probe::Vm, Support::Supported)
+            .with(Probe::NetworkAdapters, Support::Supported)
+            .with(Probe::Hosts, Support::Supported)
+            // Needs an X11/Wayland connection the agent does not open yet.
+            .with(Probe::ActiveWindow, Support::Unsupported)
+    }
+    fn list_antivirus(&self) -> Result<Vec<AntivirusInfo>, ProbeError> {
+        Err(ProbeError::Unsupported {
+            probe: "antivirus inventory",
+            platform: "Linux",
+        })
+    }
+    fn list_browsers(&self) -> Result<Vec<BrowserInfo>, ProbeError> {
+        Ok(browsers::list(self))
+    }
+    fn list_extensions(&self, browser: &BrowserInfo) -> Result<Vec<ExtensionInfo>, ProbeError> {
+        let mut extensions = Vec::new();
+        for profile in &browser.profiles {
+            extensions.extend(read_profile_extensions(&self.rooted(Path::new(profile)), &self.root)?);
+        }
+        Ok(extensions)
+    }
+    fn list_displays(&self) -> Result<Vec<DisplayInfo>, ProbeError> {
+        display::list(&self.root)
+    }
+    fn check_vm(&self) -> Result<bool, ProbeError> {
+        Ok(self.detect_vm()?.is_vm())
+    }
+    fn detect_vm(&self) -> Result<VmDetection, ProbeError> {
+        vm::detect(&self.root)
+    }
+    fn list_network_adapters(&self) -> Result<Vec<NetworkAdapterInfo>, ProbeError> {
+        network::list(&self.root)
+    }
+    fn parse_hosts_file(&self) -> Result<Vec<HostsEntry>, ProbeError> {
+        read_hosts_file(&self.root.join("etc/hosts"))
+    }
+    fn get_active_window(&self) -> Result<String, ProbeError> {
+        Err(ProbeError::Unsupported {
+            probe: "active window",
+            platform: "Linux",
+        })
     }
 }
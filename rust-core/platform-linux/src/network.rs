@@ -0,0 +1,122 @@
+use crate::read_attribute;
+use platform_common::{AdapterKind, NetworkAdapterInfo, ProbeError};
+use std::fs;
+use std::path::Path;
+
+/// `type` values from `<linux/if_arp.h>`.
+const ARPHRD_ETHER: u32 = 1;
+const ARPHRD_LOOPBACK: u32 = 772;
+
+/// Bits of `tun_flags` from `<linux/if_tun.h>`.
+const IFF_TUN: u32 = 0x0001;
+const IFF_TAP: u32 = 0x0002;
+
+/// `IFF_UP` in the interface `flags`.
+const IFF_UP: u32 = 0x1;
+
+/// Lists every interface in `<root>/sys/class/net`, sorted by name.
+///
+/// Unreadable attributes of one interface are left empty; only an
+/// unreadable directory fails.
+pub(crate) fn list(root: &Path) -> Result<Vec<NetworkAdapterInfo>, ProbeError> {
+    let net = root.join("sys/class/net");
+    let entries = fs::read_dir(&net).map_err(|e| ProbeError::io(&net, e))?;
+    let mut adapters: Vec<NetworkAdapterInfo> = entries
+        .filter_map(Result::ok)
+        .map(|entry| read_adapter(&entry.path(), &entry.file_name().to_string_lossy()))
+        .collect();
+    adapters.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(adapters)
+}
+
+fn read_adapter(dir: &Path, name: &str) -> NetworkAdapterInfo {
+    let kind = adapter_kind(dir);
+    // The bound driver is the closest thing to a Windows adapter description;
+    // virtual devices have none, so they are described by their kind.
+    let description = fs::read_link(dir.join("device/driver"))
+        .ok()
+        .and_then(|driver| Some(driver.file_name()?.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| describe(kind).to_string());
+    NetworkAdapterInfo {
+        name: name.to_string(),
+        description,
+        mac_address: read_attribute(&dir.join("address")).unwrap_or_default(),
+        kind,
+        is_up: is_up(dir),
+    }
+}
+
+fn adapter_kind(dir: &Path) -> AdapterKind {
+    if let Some(flags) = read_hex(&dir.join("tun_flags")) {
+        return if flags & IFF_TAP != 0 {
+            AdapterKind::Tap
+        } else if flags & IFF_TUN != 0 {
+            AdapterKind::Tun
+        } else {
+            AdapterKind::Other
+        };
+    }
+    match uevent_devtype(dir).as_deref() {
+        Some("wireguard") => return AdapterKind::WireGuard,
+        Some("bridge") => return AdapterKind::Bridge,
+        Some("wlan") => return AdapterKind::Wireless,
+        _ => {}
+    }
+    if dir.join("bridge").is_dir() {
+        return AdapterKind::Bridge;
+    }
+    if dir.join("wireless").is_dir() || dir.join("phy80211").exists() {
+        return AdapterKind::Wireless;
+    }
+    let arp_type = read_attribute(&dir.join("type")).and_then(|t| t.parse().ok());
+    match arp_type {
+        Some(ARPHRD_LOOPBACK) => AdapterKind::Loopback,
+        Some(ARPHRD_ETHER) if dir.join("device").exists() => AdapterKind::Ethernet,
+        // A deviceless ethernet link whose `iflink` points at another
+        // interface is one end of a veth pair.
+        Some(ARPHRD_ETHER) if is_linked_elsewhere(dir) => AdapterKind::Veth,
+        _ => AdapterKind::Other,
+    }
+}
+
+fn describe(kind: AdapterKind) -> &'static str {
+    match kind {
+        AdapterKind::Tun => "TUN device",
+        AdapterKind::Tap => "TAP device",
+        AdapterKind::WireGuard => "WireGuard tunnel",
+        AdapterKind::Bridge => "Bridge",
+        AdapterKind::Veth => "Virtual ethernet pair",
+        AdapterKind::Loopback => "Loopback",
+        _ => "",
+    }
+}
+
+/// `operstate` is `up` for interfaces with a link. Tunnels and loopback
+/// report `unknown` instead, so for those fall back to `IFF_UP`.
+fn is_up(dir: &Path) -> bool {
+    match read_attribute(&dir.join("operstate")).as_deref() {
+        Some("up") => true,
+        Some("unknown") => read_hex(&dir.join("flags")).is_some_and(|flags| flags & IFF_UP != 0),
+        _ => false,
+    }
+}
+
+fn is_linked_elsewhere(dir: &Path) -> bool {
+    let index = read_attribute(&dir.join("ifindex"));
+    let link = read_attribute(&dir.join("iflink"));
+    index.is_some() && link.is_some() && index != link
+}
+
+fn uevent_devtype(dir: &Path) -> Option<String> {
+    let uevent = fs::read_to_string(dir.join("uevent")).ok()?;
+    uevent
+        .lines()
+        .find_map(|line| line.strip_prefix("DEVTYPE="))
+        .map(str::to_string)
+}
+
+/// A `0x`-prefixed hex attribute such as `flags` or `tun_flags`.
+fn read_hex(path: &Path) -> Option<u32> {
+    let text = read_attribute(path)?;
+    u32::from_str_radix(text.trim_start_matches("0x"), 16).ok()
+}
@@ -0,0 +1,230 @@
+use platform_common::{Capabilities, Probe, ProbeError, ProcessInfo, ProcessScanner, Support};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Longest name the kernel keeps in `comm`, without the trailing NUL.
+const TASK_COMM_LEN: usize = 15;
+
+/// Clock ticks per second used by `stat` times. The kernel exports USER_HZ
+/// as 100 on every architecture we ship for.
+const USER_HZ: u64 = 100;
+
+/// Lists processes by reading `/proc/<pid>/{comm,cmdline,exe,status,stat}`,
+/// with user names from `/etc/passwd` and start times from the boot time in
+/// `/proc/stat`.
+///
+/// A process can exit between listing `/proc` and reading its files, and
+/// some files (notably `exe`) are unreadable for other users' processes.
+/// Neither is an error: missing fields are left empty and a process with
+/// nothing readable is skipped. Only an unreadable proc root fails the scan.
+#[derive(Debug, Clone)]
+pub struct LinuxScanner {
+    proc_root: PathBuf,
+    passwd: PathBuf,
+}
+
+impl Default for LinuxScanner {
+    fn default() -> Self {
+        Self::with_proc_root("/proc")
+    }
+}
+
+/// Per-scan lookups shared by every process.
+#[derive(Debug, Default)]
+struct Context {
+    /// Boot time in Unix seconds, from the `btime` line of `/proc/stat`.
+    boot_time: Option<u64>,
+    users: HashMap<u32, String>,
+}
+
+impl LinuxScanner {
+    /// Reads processes from `proc_root` instead of `/proc`, e.g. a fixture
+    /// tree in tests.
+    pub fn with_proc_root(proc_root: impl Into<PathBuf>) -> Self {
+        Self {
+            proc_root: proc_root.into(),
+            passwd: PathBuf::from("/etc/passwd"),
+        }
+    }
+
+    /// Resolves user names from `passwd` instead of `/etc/passwd`.
+    pub fn with_passwd_file(mut self, passwd: impl Into<PathBuf>) -> Self {
+        self.passwd = passwd.into();
+        self
+    }
+
+    pub fn proc_root(&self) -> &Path {
+        &self.proc_root
+    }
+
+    fn context(&self) -> Context {
+        Context {
+            boot_time: fs::read_to_string(self.proc_root.join("stat"))
+                .ok()
+                .and_then(|stat| boot_time(&stat)),
+            users: fs::read_to_string(&self.passwd)
+                .map(|passwd| parse_passwd(&passwd))
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Reads one process. `None` when it exited or nothing about it could be
+    /// read.
+    pub fn read_process(&self, pid: u32) -> Option<ProcessInfo> {
+        self.read_with(pid, &self.context())
+    }
+
+    /// Whether `pid` exists and has not exited. Zombies have exited; they
+    /// only wait for their parent to reap them.
+    pub(crate) fn is_running(&self, pid: u32) -> bool {
+        fs::read_to_string(self.proc_root.join(pid.to_string()).join("stat"))
+            .is_ok_and(|stat| !matches!(stat_fields(&stat).first(), None | Some(&"Z" | &"X")))
+    }
+
+    fn read_with(&self, pid: u32, context: &Context) -> Option<ProcessInfo> {
+        let dir = self.proc_root.join(pid.to_string());
+        let comm = read_trimmed(&dir.join("comm"));
+        let status = fs::read_to_string(dir.join("status")).ok();
+        let stat = fs::read_to_string(dir.join("stat")).ok();
+        let cmdline = fs::read(dir.join("cmdline")).ok().map(|raw| split_cmdline(&raw));
+        let exe_path = fs::read_link(dir.join("exe"))
+            .ok()
+            .map(|path| strip_deleted(&path.to_string_lossy()).to_string());
+
+        let short_name = comm
+            .or_else(|| status.as_deref().and_then(|s| status_field(s, "Name")))
+            .or_else(|| stat.as_deref().and_then(stat_comm))?;
+        let argv0 = cmdline.as_ref().and_then(|args| args.first()).map(String::as_str);
+        let name = full_name(&short_name, exe_path.as_deref(), argv0);
+
+        let stat_fields = stat.as_deref().map(stat_fields).unwrap_or_default();
+        let stat_field = |index: usize| stat_fields.get(index).and_then(|v| v.parse::<u64>().ok());
+        let uid = status
+            .as_deref()
+            .and_then(|s| status_field(s, "Uid"))
+            .and_then(|uids| uids.split_whitespace().next()?.parse().ok());
+
+        Some(ProcessInfo {
+            pid,
+            name,
+            exe_path,
+            cmdline: cmdline.filter(|args| !args.is_empty()).map(|args| args.join(" ")),
+            ppid: status
+                .as_deref()
+                .and_then(|s| status_field(s, "PPid"))
+                .and_then(|ppid| ppid.parse().ok())
+                .or_else(|| stat_field(STAT_PPID).map(|ppid| ppid as u32)),
+            uid,
+            user: uid.and_then(|uid| context.users.get(&uid).cloned()),
+            start_time: stat_field(STAT_STARTTIME)
+                .zip(context.boot_time)
+                .map(|(ticks, boot)| boot + ticks / USER_HZ),
+            start_ticks: stat_field(STAT_STARTTIME),
+            session_id: stat_field(STAT_SESSION).map(|session| session as u32),
+        })
+    }
+}
+
+impl ProcessScanner for LinuxScanner {
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new().with(Probe::Processes, Support::Supported)
+    }
+
+    fn scan(&self) -> Result<Vec<ProcessInfo>, ProbeError> {
+        let entries = fs::read_dir(&self.proc_root).map_err(|e| ProbeError::io(&self.proc_root, e))?;
+        let mut pids: Vec<u32> = entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| entry.file_name().to_str()?.parse().ok())
+            .collect();
+        pids.sort_unstable();
+        let context = self.context();
+        Ok(pids.into_iter().filter_map(|pid| self.read_with(pid, &context)).collect())
+    }
+}
+
+/// Contents of a one-line proc file. Read errors (the process exited, or
+/// the file is not readable for this user) give `None`.
+fn read_trimmed(path: &Path) -> Option<String> {
+    let text = fs::read_to_string(path).ok()?;
+    Some(text.trim_end_matches('\n').to_string()).filter(|s| !s.is_empty())
+}
+
+/// `cmdline` is NUL-separated, with a trailing NUL. Kernel threads have an
+/// empty one.
+fn split_cmdline(raw: &[u8]) -> Vec<String> {
+    raw.split(|&b| b == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| String::from_utf8_lossy(arg).into_owned())
+        .collect()
+}
+
+/// The kernel appends " (deleted)" to `exe` when the binary was removed or
+/// replaced after the process started.
+fn strip_deleted(path: &str) -> &str {
+    path.strip_suffix(" (deleted)").unwrap_or(path)
+}
+
+/// A `Key:\tvalue` line of `status`.
+fn status_field(status: &str, key: &str) -> Option<String> {
+    status.lines().find_map(|line| {
+        let (k, v) = line.split_once(':')?;
+        (k == key).then(|| v.trim().to_string())
+    })
+}
+
+/// The `comm` field of `stat`: the text between the first `(` and the last
+/// `)`, since the name itself may contain spaces and parentheses.
+fn stat_comm(stat: &str) -> Option<String> {
+    let start = stat.find('(')?;
+    let end = stat.rfind(')')?;
+    (start < end).then(|| stat[start + 1..end].to_string())
+}
+
+// Indexes into `stat_fields`, i.e. `stat` field number minus 3 (see proc(5)).
+const STAT_PPID: usize = 1;
+const STAT_SESSION: usize = 3;
+const STAT_STARTTIME: usize = 19;
+
+/// The fields of `stat` after `comm`, starting with the state.
+fn stat_fields(stat: &str) -> Vec<&str> {
+    stat.rfind(')')
+        .map(|end| stat[end + 1..].split_whitespace().collect())
+        .unwrap_or_default()
+}
+
+fn boot_time(stat: &str) -> Option<u64> {
+    stat.lines()
+        .find_map(|line| line.strip_prefix("btime "))
+        .and_then(|btime| btime.trim().parse().ok())
+}
+
+/// uid to user name, from `name:password:uid:...` lines.
+fn parse_passwd(passwd: &str) -> HashMap<u32, String> {
+    passwd
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let uid = fields.nth(1)?.parse().ok()?;
+            Some((uid, name.to_string()))
+        })
+        .collect()
+}
+
+/// `comm` is cut to 15 bytes, so "obs-studio-portable" shows up as
+/// "obs-studio-port". When it looks truncated, take the full name from the
+/// executable or argv[0] if that starts with it.
+fn full_name(comm: &str, exe_path: Option<&str>, argv0: Option<&str>) -> String {
+    if comm.len() < TASK_COMM_LEN {
+        return comm.to_string();
+    }
+    [exe_path, argv0]
+        .into_iter()
+        .flatten()
+        .map(|path| path.rsplit('/').next().unwrap_or(path))
+        .find(|base| base.len() > comm.len() && base.starts_with(comm))
+        .unwrap_or(comm)
+        .to_string()
+}
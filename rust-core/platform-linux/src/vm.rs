@@ -0,0 +1,145 @@
+use crate::read_attribute;
+use platform_common::{ProbeError, VmDetection, VmSignal};
+use std::fs;
+use std::path::Path;
+
+/// DMI files and the vendor strings hypervisors put in them, lowercase.
+const DMI_FILES: [&str; 3] = ["sys_vendor", "product_name", "board_vendor"];
+const DMI_VENDORS: [&str; 14] = [
+    "qemu",
+    "kvm",
+    "vmware",
+    "virtualbox",
+    "innotek",
+    "xen",
+    "bochs",
+    "parallels",
+    "bhyve",
+    "virtual machine",
+    "amazon ec2",
+    "google compute engine",
+    "openstack",
+    "standard pc (",
+];
+
+/// Kernel module families that only load inside a guest.
+const MODULE_FAMILIES: [(&str, &[&str]); 5] = [
+    ("virtio", &["virtio_"]),
+    ("vmware", &["vmw_", "vmwgfx"]),
+    ("virtualbox", &["vboxguest", "vboxsf", "vboxvideo"]),
+    ("hyper-v", &["hv_vmbus", "hv_netvsc", "hv_storvsc", "hv_utils", "hyperv_"]),
+    ("xen", &["xen_", "xenfs"]),
+];
+
+/// MAC prefixes assigned to virtual NICs.
+const VM_OUIS: [(&str, &str); 9] = [
+    ("08:00:27", "virtualbox"),
+    ("00:05:69", "vmware"),
+    ("00:0c:29", "vmware"),
+    ("00:1c:14", "vmware"),
+    ("00:50:56", "vmware"),
+    ("52:54:00", "qemu"),
+    ("00:16:3e", "xen"),
+    ("00:15:5d", "hyper-v"),
+    ("00:1c:42", "parallels"),
+];
+
+const DMI_WEIGHT: u8 = 90;
+const HYPERVISOR_TYPE_WEIGHT: u8 = 80;
+const CPU_FLAG_WEIGHT: u8 = 60;
+const MAC_WEIGHT: u8 = 50;
+const MODULE_WEIGHT: u8 = 40;
+
+/// Looks for hypervisor artifacts under `root` (`/` on a live system).
+///
+/// Errors only when none of DMI, `/proc/cpuinfo` and `/proc/modules` could
+/// be read, since then "no signals" would mean "could not look".
+pub(crate) fn detect(root: &Path) -> Result<VmDetection, ProbeError> {
+    let mut signals = Vec::new();
+    let mut readable = false;
+
+    for file in DMI_FILES {
+        let Some(value) = read_attribute(&root.join("sys/class/dmi/id").join(file)) else {
+            continue;
+        };
+        readable = true;
+        let lower = value.to_lowercase();
+        if DMI_VENDORS.iter().any(|vendor| lower.contains(vendor)) {
+            signals.push(VmSignal::new(format!("dmi:{}", file), value, DMI_WEIGHT));
+        }
+    }
+
+    if let Ok(cpuinfo) = fs::read_to_string(root.join("proc/cpuinfo")) {
+        readable = true;
+        if cpu_flags(&cpuinfo).any(|flag| flag == "hypervisor") {
+            signals.push(VmSignal::new("cpuinfo:hypervisor", "hypervisor", CPU_FLAG_WEIGHT));
+        }
+    }
+
+    if let Some(kind) = read_attribute(&root.join("sys/hypervisor/type")) {
+        signals.push(VmSignal::new("sys:hypervisor", kind, HYPERVISOR_TYPE_WEIGHT));
+    }
+
+    if let Ok(modules) = fs::read_to_string(root.join("proc/modules")) {
+        readable = true;
+        signals.extend(module_signals(&modules));
+    }
+
+    signals.extend(mac_signals(&root.join("sys/class/net")));
+
+    if !readable {
+        return Err(ProbeError::Unavailable {
+            message: format!("no VM detection source is readable under {}", root.display()),
+        });
+    }
+    Ok(VmDetection::from_signals(signals))
+}
+
+/// The `flags` of the first CPU; every CPU lists the same ones.
+fn cpu_flags(cpuinfo: &str) -> impl Iterator<Item = &str> {
+    cpuinfo
+        .lines()
+        .find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            (key.trim() == "flags").then_some(value)
+        })
+        .unwrap_or_default()
+        .split_whitespace()
+}
+
+/// One signal per module family, listing the loaded modules of it.
+fn module_signals(modules: &str) -> Vec<VmSignal> {
+    let loaded: Vec<&str> = modules.lines().filter_map(|line| line.split_whitespace().next()).collect();
+    MODULE_FAMILIES
+        .iter()
+        .filter_map(|(family, prefixes)| {
+            let found: Vec<&str> = loaded
+                .iter()
+                .copied()
+                .filter(|module| prefixes.iter().any(|prefix| module.starts_with(prefix)))
+                .collect();
+            (!found.is_empty()).then(|| VmSignal::new(format!("module:{}", family), found.join(","), MODULE_WEIGHT))
+        })
+        .collect()
+}
+
+/// Virtual NIC prefixes on interfaces backed by a device. Bridges and
+/// tap devices of a VM host (e.g. libvirt's `virbr0`) have no `device`
+/// link, so a host running VMs is not mistaken for a guest.
+fn mac_signals(net: &Path) -> Vec<VmSignal> {
+    let Ok(entries) = fs::read_dir(net) else {
+        return Vec::new();
+    };
+    let mut interfaces: Vec<_> = entries.filter_map(Result::ok).map(|entry| entry.path()).collect();
+    interfaces.sort();
+    interfaces
+        .iter()
+        .filter(|interface| interface.join("device").exists())
+        .filter_map(|interface| {
+            let mac = read_attribute(&interface.join("address"))?.to_lowercase();
+            let (_, vendor) = VM_OUIS.iter().find(|(oui, _)| mac.starts_with(oui))?;
+            let name = interface.file_name()?.to_string_lossy();
+            Some(VmSignal::new(format!("mac:{}", name), format!("{} ({})", mac, vendor), MAC_WEIGHT))
+        })
+        .collect()
+}
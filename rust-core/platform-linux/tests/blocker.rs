@@ -0,0 +1,123 @@
+use platform_common::{BlockResult, Blocker, ProcessInfo};
+use platform_linux::{LinuxBlocker, LinuxScanner};
+use std::fs;
+use std::process::{Child, Command};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Bit of SIGTERM in the `SigIgn` mask of `/proc/<pid>/status`.
+const SIGTERM_MASK: u64 = 1 << (15 - 1);
+
+fn blocker() -> LinuxBlocker {
+    LinuxBlocker::default().with_grace_period(Duration::from_millis(300))
+}
+
+fn observe(child: &Child) -> ProcessInfo {
+    LinuxScanner::default().read_process(child.id()).unwrap()
+}
+
+fn spawn(script: &str) -> Child {
+    Command::new("sh").args(["-c", script]).spawn().unwrap()
+}
+
+/// Waits until the shell has installed its SIGTERM trap.
+fn wait_for_ignored_sigterm(child: &Child) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline {
+        let status = fs::read_to_string(format!("/proc/{}/status", child.id())).unwrap();
+        let ignored = status
+            .lines()
+            .find_map(|line| line.strip_prefix("SigIgn:"))
+            .and_then(|mask| u64::from_str_radix(mask.trim(), 16).ok())
+            .unwrap_or_default();
+        if ignored & SIGTERM_MASK != 0 {
+            return;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    panic!("SIGTERM trap was never installed");
+}
+
+#[test]
+fn terminates_a_cooperating_process() {
+    let mut child = spawn("exec sleep 30");
+    let process = observe(&child);
+    assert_eq!(blocker().block(&process), BlockResult::Terminated);
+    assert!(child.wait().unwrap().code().is_none());
+}
+
+#[test]
+fn kills_a_process_that_ignores_sigterm() {
+    let mut child = spawn("trap '' TERM; while :; do sleep 0.1; done");
+    wait_for_ignored_sigterm(&child);
+    let process = observe(&child);
+    assert_eq!(blocker().block(&process), BlockResult::Killed);
+    child.wait().unwrap();
+}
+
+#[test]
+fn exited_process_is_reported_as_such() {
+    let mut child = spawn("exit 0");
+    let process = ProcessInfo {
+        pid: child.id(),
+        ..Default::default()
+    };
+    child.wait().unwrap();
+    assert_eq!(blocker().block(&process), BlockResult::AlreadyExited);
+}
+
+#[test]
+fn reused_pid_is_left_alone() {
+    let mut child = spawn("exec sleep 30");
+    // Same pid, but observed one clock tick earlier: within the same second.
+    let stale = ProcessInfo {
+        start_ticks: observe(&child).start_ticks.map(|ticks| ticks - 1),
+        ..observe(&child)
+    };
+    assert_eq!(blocker().block(&stale), BlockResult::PidReused);
+    assert!(child.try_wait().unwrap().is_none(), "the new process must keep running");
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
+
+#[test]
+fn process_with_unknown_start_time_is_left_alone() {
+    let mut child = spawn("exec sleep 30");
+    let unknown = ProcessInfo {
+        start_ticks: None,
+        ..observe(&child)
+    };
+    assert!(matches!(blocker().block(&unknown), BlockResult::Failed(_)));
+    assert!(child.try_wait().unwrap().is_none(), "the process must keep running");
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
+
+#[test]
+fn stops_several_processes_within_one_grace_period() {
+    let mut children: Vec<Child> = (0..3)
+        .map(|_| spawn("trap '' TERM; while :; do sleep 0.1; done"))
+        .collect();
+    children.iter().for_each(wait_for_ignored_sigterm);
+    let processes: Vec<ProcessInfo> = children.iter().map(observe).collect();
+    let processes: Vec<&ProcessInfo> = processes.iter().collect();
+    let blocker = LinuxBlocker::default().with_grace_period(Duration::from_secs(1));
+
+    let started = Instant::now();
+    let results = blocker.block_all(&processes);
+    assert!(
+        started.elapsed() < Duration::from_secs(2),
+        "took {:?}",
+        started.elapsed()
+    );
+    assert_eq!(results, vec![BlockResult::Killed; 3]);
+    for child in &mut children {
+        child.wait().unwrap();
+    }
+}
+
+#[test]
+fn pid_zero_is_never_signalled() {
+    let group = ProcessInfo::default();
+    assert!(matches!(blocker().block(&group), BlockResult::Failed(_)));
+}
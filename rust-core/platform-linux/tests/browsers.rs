@@ -0,0 +1,109 @@
+use platform_common::{BrowserFamily, BrowserInfo, SystemProfiler};
+use platform_linux::LinuxProfiler;
+
+fn browsers() -> Vec<BrowserInfo> {
+    LinuxProfiler::with_root(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/browsers"))
+        .with_home("/home/student")
+        .list_browsers()
+        .unwrap()
+}
+
+fn find<'a>(browsers: &'a [BrowserInfo], install_path: &str) -> &'a BrowserInfo {
+    browsers
+        .iter()
+        .find(|b| b.install_path == install_path)
+        .unwrap_or_else(|| panic!("no browser at {}", install_path))
+}
+
+#[test]
+fn lists_desktop_entries_flatpaks_and_snaps() {
+    let browsers = browsers();
+    let summary: Vec<_> = browsers.iter().map(|b| (b.name.as_str(), b.install_path.as_str())).collect();
+    assert_eq!(
+        summary,
+        [
+            ("Brave Web Browser", "/var/lib/flatpak/app/com.brave.Browser"),
+            ("Chromium Web Browser", "/snap/bin/chromium"),
+            ("Firefox Web Browser", "/usr/bin/firefox"),
+            ("Google Chrome", "/usr/bin/google-chrome-stable"),
+            ("Tor Browser", "/opt/Tor Browser/start-tor-browser"),
+            // Snap without a desktop entry.
+            ("firefox", "/snap/bin/firefox"),
+        ]
+    );
+}
+
+#[test]
+fn detects_versions() {
+    let browsers = browsers();
+    let version = |path| find(&browsers, path).version.as_deref();
+    assert_eq!(version("/var/lib/flatpak/app/com.brave.Browser"), Some("1.68.137"));
+    assert_eq!(version("/snap/bin/chromium"), Some("126.0.6478.126"));
+    assert_eq!(version("/snap/bin/firefox"), Some("129.0-2"));
+    assert_eq!(version("/usr/bin/firefox"), Some("128.0.3"));
+    assert_eq!(version("/usr/bin/google-chrome-stable"), Some("127.0.6533.88"));
+    assert_eq!(version("/opt/Tor Browser/start-tor-browser"), None);
+}
+
+#[test]
+fn finds_every_chromium_profile() {
+    let browsers = browsers();
+    let chrome = find(&browsers, "/usr/bin/google-chrome-stable");
+    let root = "/home/student/.config/google-chrome";
+    assert_eq!(
+        chrome.profiles,
+        [
+            format!("{}/Default", root),
+            format!("{}/Profile 1", root),
+            format!("{}/Profile 10", root),
+        ]
+    );
+    let brave = find(&browsers, "/var/lib/flatpak/app/com.brave.Browser");
+    assert_eq!(brave.profiles.len(), 2);
+    assert!(brave.profiles[0].starts_with("/home/student/.var/app/com.brave.Browser/"));
+    let chromium = find(&browsers, "/snap/bin/chromium");
+    assert_eq!(chromium.profiles, ["/home/student/snap/chromium/common/chromium/Default"]);
+}
+
+#[test]
+fn reads_firefox_profiles_ini() {
+    let browsers = browsers();
+    assert_eq!(
+        find(&browsers, "/usr/bin/firefox").profiles,
+        [
+            "/home/student/work-profile",
+            "/home/student/.mozilla/firefox/abcd1234.default-release",
+        ]
+    );
+    assert_eq!(
+        find(&browsers, "/snap/bin/firefox").profiles,
+        ["/home/student/snap/firefox/common/.mozilla/firefox/snap0001.default"]
+    );
+}
+
+#[test]
+fn without_a_home_only_system_entries_are_listed() {
+    let browsers = LinuxProfiler::with_root(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/browsers"))
+        .list_browsers()
+        .unwrap();
+    // The user's Hidden=true override is not seen, so Vivaldi shows up.
+    assert!(browsers.iter().any(|b| b.name == "Vivaldi"));
+    assert!(browsers.iter().all(|b| b.profiles.is_empty()));
+}
+
+#[test]
+fn lists_extensions_of_chromium_and_firefox_profiles() {
+    let profiler = LinuxProfiler::with_root(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/browsers"))
+        .with_home("/home/student");
+    let browsers = profiler.list_browsers().unwrap();
+    let chrome = find(&browsers, "/usr/bin/google-chrome-stable");
+    let extensions = profiler.list_extensions(chrome).unwrap();
+    let names: Vec<_> = extensions.iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, ["Quizlet Helper"]);
+    assert!(extensions.iter().all(|e| e.family == BrowserFamily::Chromium));
+
+    // Firefox profiles are read from extensions.json; the work profile has none.
+    let extensions = profiler.list_extensions(find(&browsers, "/usr/bin/firefox")).unwrap();
+    let names: Vec<_> = extensions.iter().map(|e| (e.name.as_str(), e.family)).collect();
+    assert_eq!(names, [("ChatGPT for Firefox", BrowserFamily::Gecko)]);
+}
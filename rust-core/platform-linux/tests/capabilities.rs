@@ -0,0 +1,28 @@
+use platform_common::{Probe, ProcessScanner, Support, SystemProfiler, SystemSnapshot};
+use platform_linux::{LinuxProfiler, LinuxScanner};
+
+fn profiler() -> LinuxProfiler {
+    LinuxProfiler::with_root(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/vm/kvm"))
+}
+
+fn scanner() -> LinuxScanner {
+    LinuxScanner::with_proc_root(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/proc"))
+}
+
+#[test]
+fn every_probe_is_declared() {
+    let capabilities = SystemProfiler::capabilities(&profiler()).merge(ProcessScanner::capabilities(&scanner()));
+    let declared: Vec<Probe> = capabilities.iter().map(|(probe, _)| probe).collect();
+    assert_eq!(declared, Probe::ALL);
+    assert_eq!(capabilities.support(Probe::Processes), Support::Supported);
+    assert_eq!(capabilities.support(Probe::Antivirus), Support::Unsupported);
+}
+
+#[test]
+fn probes_declared_unsupported_report_unsupported() {
+    let snapshot = SystemSnapshot::capture(&profiler(), &scanner(), "test");
+    for (probe, support) in snapshot.capabilities.iter() {
+        let unsupported = snapshot.status(probe).is_some_and(|status| status.is_unsupported());
+        assert_eq!(unsupported, support == Support::Unsupported, "{}: {:?}", probe, snapshot.status(probe));
+    }
+}
@@ -0,0 +1,64 @@
+use platform_common::{ConnectorKind, DisplayFlag, DisplayInfo, SystemProfiler};
+use platform_linux::LinuxProfiler;
+
+fn displays() -> Vec<DisplayInfo> {
+    LinuxProfiler::with_root(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/drm"))
+        .list_displays()
+        .unwrap()
+}
+
+fn find<'a>(displays: &'a [DisplayInfo], device_id: &str) -> &'a DisplayInfo {
+    displays
+        .iter()
+        .find(|d| d.device_id == device_id)
+        .unwrap_or_else(|| panic!("no display {}", device_id))
+}
+
+#[test]
+fn lists_connected_connectors_only() {
+    let ids: Vec<_> = displays().into_iter().map(|d| d.device_id).collect();
+    // DP-1 is disconnected and writeback connectors are not displays.
+    assert_eq!(
+        ids,
+        ["card0-DP-2", "card0-DP-3", "card0-HDMI-A-1", "card0-HDMI-A-2", "card0-eDP-1", "card1-Virtual-1"]
+    );
+}
+
+#[test]
+fn reads_connector_type_and_edid() {
+    let displays = displays();
+    let monitor = find(&displays, "card0-HDMI-A-1");
+    assert_eq!(monitor.name, "DELL U2720Q");
+    assert_eq!(monitor.connector, ConnectorKind::Hdmi);
+    assert!(!monitor.is_internal);
+    assert!(monitor.is_enabled);
+    let edid = monitor.edid.as_ref().unwrap();
+    assert_eq!((edid.manufacturer.as_str(), edid.serial.as_deref()), ("DEL", Some("ABC1234")));
+    assert_eq!((edid.width_mm, edid.height_mm), (Some(597), Some(336)));
+
+    // A panel without a name descriptor is named by vendor and product.
+    let panel = find(&displays, "card0-eDP-1");
+    assert_eq!(panel.name, "BOE 0A8E");
+    assert_eq!(panel.connector, ConnectorKind::EmbeddedDisplayPort);
+    assert!(panel.is_internal);
+}
+
+#[test]
+fn flags_dummy_plugs_and_capture_devices() {
+    let displays = displays();
+    let dummy = find(&displays, "card0-HDMI-A-2");
+    assert_eq!(dummy.flag, Some(DisplayFlag::DummyPlug));
+    assert!(!dummy.is_enabled);
+    assert_eq!(find(&displays, "card0-DP-2").flag, Some(DisplayFlag::CaptureDevice));
+    assert_eq!(find(&displays, "card0-HDMI-A-1").flag, None);
+}
+
+#[test]
+fn connectors_without_a_valid_edid_are_named_after_the_connector() {
+    let displays = displays();
+    let corrupt = find(&displays, "card0-DP-3");
+    assert_eq!((corrupt.name.as_str(), corrupt.edid.is_none()), ("DP-3", true));
+    let virtual_output = find(&displays, "card1-Virtual-1");
+    assert_eq!(virtual_output.name, "Virtual-1");
+    assert_eq!(virtual_output.connector, ConnectorKind::Virtual);
+}
@@ -0,0 +1,43 @@
+use platform_common::{AdapterKind, SystemProfiler};
+use platform_linux::LinuxProfiler;
+
+fn fixture() -> LinuxProfiler {
+    LinuxProfiler::with_root(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/net"))
+}
+
+#[test]
+fn classifies_every_interface() {
+    let adapters = fixture().list_network_adapters().unwrap();
+    let summary: Vec<_> = adapters.iter().map(|a| (a.name.as_str(), a.kind, a.is_up)).collect();
+    assert_eq!(
+        summary,
+        [
+            ("docker0", AdapterKind::Bridge, false),
+            ("enp3s0", AdapterKind::Ethernet, true),
+            ("lo", AdapterKind::Loopback, true),
+            ("tap0", AdapterKind::Tap, false),
+            ("tun0", AdapterKind::Tun, true),
+            ("veth1a2b3c", AdapterKind::Veth, true),
+            ("wg0", AdapterKind::WireGuard, true),
+            ("wlp2s0", AdapterKind::Wireless, false),
+        ]
+    );
+}
+
+#[test]
+fn describes_by_driver_or_kind() {
+    let adapters = fixture().list_network_adapters().unwrap();
+    let find = |name: &str| adapters.iter().find(|a| a.name == name).unwrap();
+    assert_eq!(find("enp3s0").description, "e1000e");
+    assert_eq!(find("enp3s0").mac_address, "3c:ec:ef:01:02:03");
+    assert_eq!(find("wlp2s0").description, "iwlwifi");
+    assert_eq!(find("wg0").description, "WireGuard tunnel");
+    // Layer 3 tunnels have no hardware address.
+    assert_eq!(find("tun0").mac_address, "");
+}
+
+#[test]
+fn missing_sysfs_is_an_error() {
+    let err = LinuxProfiler::with_root("/nonexistent").list_network_adapters().unwrap_err();
+    assert!(err.to_string().contains("/nonexistent/sys/class/net"), "{}", err);
+}
@@ -0,0 +1,75 @@
+use platform_common::ProcessScanner;
+use platform_linux::LinuxScanner;
+
+fn fixture() -> LinuxScanner {
+    LinuxScanner::with_proc_root(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/proc"))
+}
+
+#[test]
+fn scans_fixture_tree() {
+    let processes = fixture().scan().unwrap();
+    let summary: Vec<_> = processes.iter().map(|p| (p.pid, p.name.as_str())).collect();
+    assert_eq!(
+        summary,
+        [(1, "systemd"), (2, "kthreadd"), (4242, "obs"), (5001, "obs-studio-portable"), (6100, "(sd-pam)")]
+    );
+}
+
+#[test]
+fn reads_exe_link() {
+    let scanner = fixture();
+    let obs = scanner.read_process(4242).unwrap();
+    assert_eq!(obs.exe_path.as_deref(), Some("/usr/bin/obs"));
+
+    let deleted = scanner.read_process(5001).unwrap();
+    assert_eq!(deleted.exe_path.as_deref(), Some("/opt/obs/obs-studio-portable"));
+
+    let kernel_thread = scanner.read_process(2).unwrap();
+    assert_eq!(kernel_thread.exe_path, None);
+}
+
+#[test]
+fn reads_process_details() {
+    let scanner = fixture().with_passwd_file(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/passwd"));
+    let obs = scanner.read_process(4242).unwrap();
+    assert_eq!(obs.cmdline.as_deref(), Some("/usr/bin/obs --startrecording"));
+    assert_eq!(obs.ppid, Some(2001));
+    assert_eq!(obs.uid, Some(1000));
+    assert_eq!(obs.user.as_deref(), Some("student"));
+    assert_eq!(obs.session_id, Some(2001));
+    // btime 1767225600 plus 881234 ticks at USER_HZ 100.
+    assert_eq!(obs.start_time, Some(1767234412));
+
+    // Only `stat` is readable: the parent and session still come from it.
+    let sd_pam = scanner.read_process(6100).unwrap();
+    assert_eq!(sd_pam.cmdline, None);
+    assert_eq!(sd_pam.uid, None);
+    assert_eq!(sd_pam.user, None);
+    assert!(sd_pam.ppid.is_some());
+}
+
+#[test]
+fn exited_and_missing_processes_are_skipped() {
+    let scanner = fixture();
+    assert!(scanner.read_process(6000).is_none());
+    assert!(scanner.read_process(99999).is_none());
+}
+
+#[test]
+fn missing_proc_root_is_an_error() {
+    let err = LinuxScanner::with_proc_root("/nonexistent/proc").scan().unwrap_err();
+    assert_eq!(err.kind(), "io");
+    assert!(err.to_string().contains("/nonexistent/proc"), "{}", err);
+}
+
+#[test]
+fn live_proc_contains_this_process() {
+    let scanner = LinuxScanner::default();
+    if !scanner.proc_root().exists() {
+        return;
+    }
+    let pid = std::process::id();
+    let processes = scanner.scan().unwrap();
+    let me = processes.iter().find(|p| p.pid == pid).expect("own pid listed");
+    assert!(me.exe_path.is_some());
+}
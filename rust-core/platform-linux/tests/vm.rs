@@ -0,0 +1,66 @@
+use platform_common::{SystemProfiler, VmDetection, VmSignal};
+use platform_linux::LinuxProfiler;
+
+fn fixture(name: &str) -> LinuxProfiler {
+    LinuxProfiler::with_root(format!("{}/tests/fixtures/vm/{}", env!("CARGO_MANIFEST_DIR"), name))
+}
+
+fn sources(detection: &VmDetection) -> Vec<&str> {
+    detection.signals.iter().map(|s| s.source.as_str()).collect()
+}
+
+#[test]
+fn kvm_guest_is_detected() {
+    let detection = fixture("kvm").detect_vm().unwrap();
+    assert_eq!(
+        sources(&detection),
+        ["dmi:sys_vendor", "dmi:product_name", "cpuinfo:hypervisor", "module:virtio", "mac:ens3"]
+    );
+    assert_eq!(detection.signals[3].value, "virtio_net,virtio_blk");
+    assert_eq!(detection.signals[4].value, "52:54:00:12:34:56 (qemu)");
+    assert_eq!(detection.confidence, 100);
+    assert!(fixture("kvm").check_vm().unwrap());
+}
+
+#[test]
+fn virtualbox_guest_is_detected() {
+    let detection = fixture("vbox").detect_vm().unwrap();
+    assert_eq!(
+        sources(&detection),
+        ["dmi:sys_vendor", "dmi:product_name", "cpuinfo:hypervisor", "module:virtualbox", "mac:enp0s3"]
+    );
+    assert_eq!(detection.signals[3].value, "vboxsf,vboxguest,vboxvideo");
+    assert!(detection.is_vm());
+}
+
+#[test]
+fn vm_host_is_not_a_guest() {
+    // kvm modules and a QEMU-prefixed bridge belong to the host side.
+    let detection = fixture("libvirt-host").detect_vm().unwrap();
+    assert_eq!(detection, VmDetection::default());
+    assert!(!fixture("libvirt-host").check_vm().unwrap());
+}
+
+#[test]
+fn hypervisor_type_alone_is_enough() {
+    let detection = fixture("xen").detect_vm().unwrap();
+    assert_eq!(detection.signals, [VmSignal::new("sys:hypervisor", "xen", 80)]);
+    assert_eq!(detection.confidence, 80);
+    assert!(detection.is_vm());
+}
+
+#[test]
+fn confidence_combines_independent_signals() {
+    let weak = |n| VmDetection::from_signals(vec![VmSignal::new("module:virtio", "virtio_net", 40); n]);
+    assert_eq!(weak(1).confidence, 40);
+    assert!(!weak(1).is_vm());
+    assert_eq!(weak(2).confidence, 64);
+    assert!(weak(2).is_vm());
+}
+
+#[test]
+fn unreadable_root_is_an_error() {
+    let err = LinuxProfiler::with_root("/nonexistent").detect_vm().unwrap_err();
+    assert_eq!(err.kind(), "unavailable");
+    assert!(err.to_string().contains("/nonexistent"), "{}", err);
+}
@@ -1,47 +1,80 @@
 use platform_common::{
-    AntivirusInfo, Blocker, BrowserInfo, DisplayInfo, ExtensionInfo, HostsEntry, NetworkAdapterInfo,
-    ProcessInfo, ProcessScanner, SystemProfiler,
+    AntivirusInfo, BlockResult, Blocker, BrowserInfo, Capabilities, DisplayInfo, ExtensionInfo, HostsEntry,
+    NetworkAdapterInfo, ProbeError, ProcessInfo, ProcessScanner, SystemProfiler,
 };
 
 pub struct MacProfiler;
 impl SystemProfiler for MacProfiler {
-    fn list_antivirus(&self) -> Result<Vec<AntivirusInfo>, String> {
-        Ok(vec![])
+    /// Nothing is implemented yet; every probe is unsupported.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new()
     }
-    fn list_browsers(&self) -> Result<Vec<BrowserInfo>, String> {
-        Ok(vec![])
+    fn list_antivirus(&self) -> Result<Vec<AntivirusInfo>, ProbeError> {
+        Err(ProbeError::Unsupported {
+            probe: "antivirus inventory",
+            platform: "macOS",
+        })
     }
-    fn list_extensions(&self, _browser: &BrowserInfo) -> Result<Vec<ExtensionInfo>, String> {
-        Ok(vec![])
+    fn list_browsers(&self) -> Result<Vec<BrowserInfo>, ProbeError> {
+        Err(ProbeError::Unsupported {
+            probe: "browser inventory",
+            platform: "macOS",
+        })
     }
-    fn list_displays(&self) -> Result<Vec<DisplayInfo>, String> {
-        Ok(vec![])
+    fn list_extensions(&self, _browser: &BrowserInfo) -> Result<Vec<ExtensionInfo>, ProbeError> {
+        Err(ProbeError::Unsupported {
+            probe: "extension inventory",
+            platform: "macOS",
+        })
     }
-    fn check_vm(&self) -> Result<bool, String> {
-        Ok(false)
+    fn list_displays(&self) -> Result<Vec<DisplayInfo>, ProbeError> {
+        Err(ProbeError::Unsupported {
+            probe: "display enumeration",
+            platform: "macOS",
+        })
     }
-    fn list_network_adapters(&self) -> Result<Vec<NetworkAdapterInfo>, String> {
-        Ok(vec![])
+    fn check_vm(&self) -> Result<bool, ProbeError> {
+        Err(ProbeError::Unsupported {
+            probe: "VM detection",
+            platform: "macOS",
+        })
     }
-    fn parse_hosts_file(&self) -> Result<Vec<HostsEntry>, String> {
-        Ok(vec![])
+    fn list_network_adapters(&self) -> Result<Vec<NetworkAdapterInfo>, ProbeError> {
+        Err(ProbeError::Unsupported {
+            probe: "network adapter inventory",
+            platform: "macOS",
+        })
     }
-    fn get_active_window(&self) -> Result<String, String> {
-        Ok("Mac Window".to_string())
+    fn parse_hosts_file(&self) -> Result<Vec<HostsEntry>, ProbeError> {
+        Err(ProbeError::Unsupported {
+            probe: "hosts file check",
+            platform: "macOS",
+        })
+    }
+    fn get_active_window(&self) -> Result<String, ProbeError> {
+        Err(ProbeError::Unsupported {
+            probe: "active window",
+            platform: "macOS",
+        })
     }
 }
 
 pub struct MacScanner;
 impl ProcessScanner for MacScanner {
-    fn scan(&self) -> Result<Vec<ProcessInfo>, String> {
-        Ok(vec![])
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new()
+    }
+    fn scan(&self) -> Result<Vec<ProcessInfo>, ProbeError> {
+        Err(ProbeError::Unsupported {
+            probe: "process scan",
+            platform: "macOS",
+        })
     }
 }
 
 pub struct MacBlocker;
 impl Blocker for MacBlocker {
-    fn block(&self, pid: u32) -> bool {
-        println!("Blocking process {} on macOS", pid);
-        true
+    fn block(&self, _process: &ProcessInfo) -> BlockResult {
+        BlockResult::Unsupported
     }
 }
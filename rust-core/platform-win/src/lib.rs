@@ -1,12 +1,10 @@
 use platform_common::{
-    AntivirusInfo, Blocker, BrowserInfo, DisplayInfo, ExtensionInfo, HostsEntry, NetworkAdapterInfo,
-    ProcessInfo, ProcessScanner, SystemProfiler,
+    chromium_profiles, firefox_profiles, read_hosts_file, read_profile_extensions, AdapterKind, AntivirusInfo,
+    BlockResult, Blocker, BrowserInfo, Capabilities, DisplayInfo, ExtensionInfo, HostsEntry, NetworkAdapterInfo,
+    Probe, ProbeError, ProcessInfo, ProcessScanner, Support, SystemProfiler,
 };
 use serde::Deserialize;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
-use walkdir::WalkDir;
+use std::path::{Path, PathBuf};
 use winreg::enums::*;
 use winreg::RegKey;
 use wmi::{COMLibrary, WMIConnection};
@@ -20,12 +18,17 @@ struct Win32NetworkAdapter {
     description: Option<String>,
     m_a_c_address: Option<String>,
     net_connection_status: Option<u16>,
+    service_name: Option<String>,
 }
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "PascalCase")]
 struct Win32Process {
     process_id: u32,
     name: String,
+    executable_path: Option<String>,
+    command_line: Option<String>,
+    parent_process_id: Option<u32>,
+    session_id: Option<u32>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -54,15 +57,42 @@ struct PnPDevice {
     status: Option<String>,
 }
 
+/// Kind of an adapter from the driver service behind it. Unrecognized
+/// drivers are left `Unknown` rather than guessed from the name.
+fn adapter_kind(service_name: Option<&str>) -> AdapterKind {
+    match service_name.map(str::to_lowercase).as_deref() {
+        Some("tap0901" | "tapwindows6") => AdapterKind::Tap,
+        Some("wintun") => AdapterKind::Tun,
+        Some("wireguard") => AdapterKind::WireGuard,
+        _ => AdapterKind::Unknown,
+    }
+}
+
 impl SystemProfiler for WindowsProfiler {
-    fn list_antivirus(&self) -> Result<Vec<AntivirusInfo>, String> {
-        let com_con = COMLibrary::new().map_err(|e| e.to_string())?;
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new()
+            .with(Probe::Antivirus, Support::Supported)
+            // Registry clients only, without versions.
+            .with(Probe::Browsers, Support::Partial)
+            // Chrome, Edge and Firefox profiles only.
+            .with(Probe::Extensions, Support::Partial)
+            // PnP monitors, without connectors or EDID.
+            .with(Probe::Displays, Support::Partial)
+            // Manufacturer/model keywords, no signals.
+            .with(Probe::Vm, Support::Partial)
+            .with(Probe::NetworkAdapters, Support::Supported)
+            .with(Probe::Hosts, Support::Supported)
+            .with(Probe::ActiveWindow, Support::Supported)
+    }
+
+    fn list_antivirus(&self) -> Result<Vec<AntivirusInfo>, ProbeError> {
+        let com_con = COMLibrary::new().map_err(|e| ProbeError::os("failed to initialize COM", e))?;
         let wmi_con = WMIConnection::with_namespace_path("ROOT\\SecurityCenter2", com_con)
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| ProbeError::os("failed to connect to WMI namespace ROOT\\SecurityCenter2", e))?;
 
         let results: Vec<AntiVirusProduct> = wmi_con
             .raw_query("SELECT * FROM AntivirusProduct")
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| ProbeError::os("WMI query failed", e))?;
 
         let mut av_infos = Vec::new();
         for av in results {
@@ -80,7 +110,7 @@ impl SystemProfiler for WindowsProfiler {
         Ok(av_infos)
     }
 
-    fn list_browsers(&self) -> Result<Vec<BrowserInfo>, String> {
+    fn list_browsers(&self) -> Result<Vec<BrowserInfo>, ProbeError> {
         let mut browsers = Vec::new();
         let mut seen_names = std::collections::HashSet::new();
         let hives = [HKEY_LOCAL_MACHINE, HKEY_CURRENT_USER];
@@ -108,7 +138,7 @@ impl SystemProfiler for WindowsProfiler {
                         String::new()
                     };
 
-                    // Detect Profiles (for Chrome/Edge)
+                    // Detect Profiles (for Chrome/Edge/Firefox)
                     let mut profiles = Vec::new();
                     let local_app_data = std::env::var("LOCALAPPDATA").unwrap_or_default();
                     let mut user_data_path = PathBuf::new();
@@ -121,9 +151,15 @@ impl SystemProfiler for WindowsProfiler {
                          user_data_path.push("Microsoft\\Edge\\User Data");
                     }
 
-                    if user_data_path.exists() {
-                         profiles.push(user_data_path.to_string_lossy().to_string());
-                         // Could scan for "Profile *" folders here too
+                    for profile in chromium_profiles(&user_data_path) {
+                        profiles.push(profile.to_string_lossy().to_string());
+                    }
+                    if name.to_lowercase().contains("firefox") {
+                        let app_data = std::env::var("APPDATA").unwrap_or_default();
+                        let profiles_dir = Path::new(&app_data).join("Mozilla\\Firefox");
+                        for profile in firefox_profiles(&profiles_dir, Path::new("/")) {
+                            profiles.push(profile.to_string_lossy().to_string());
+                        }
                     }
 
                     browsers.push(BrowserInfo {
@@ -139,79 +175,51 @@ impl SystemProfiler for WindowsProfiler {
         Ok(browsers)
     }
 
-    fn list_extensions(&self, browser: &BrowserInfo) -> Result<Vec<ExtensionInfo>, String> {
+    fn list_extensions(&self, browser: &BrowserInfo) -> Result<Vec<ExtensionInfo>, ProbeError> {
         let mut extensions = Vec::new();
         for profile_path in &browser.profiles {
-            let extensions_dir = PathBuf::from(profile_path).join("Default\\Extensions");
-            if !extensions_dir.exists() {
-                continue;
-            }
-
-            for entry in WalkDir::new(extensions_dir).min_depth(1).max_depth(1) {
-                let entry = entry.map_err(|e| e.to_string())?;
-                if !entry.file_type().is_dir() { continue; }
-                
-                let id = entry.file_name().to_string_lossy().to_string();
-                
-                // Find version dir inside
-                for ver_entry in WalkDir::new(entry.path()).min_depth(1).max_depth(1) {
-                     let ver_entry = ver_entry.map_err(|e| e.to_string())?;
-                     if !ver_entry.file_type().is_dir() { continue; }
-                     let version = ver_entry.file_name().to_string_lossy().to_string();
-                     
-                     // Read manifest.json for Name
-                     let manifest_path = ver_entry.path().join("manifest.json");
-                     if manifest_path.exists() {
-                         if let Ok(file) = std::fs::File::open(manifest_path) {
-                             let json: serde_json::Value = serde_json::from_reader(file).unwrap_or_default();
-                             let name = json["name"].as_str().unwrap_or(&id).to_string();
-                             
-                             extensions.push(ExtensionInfo {
-                                 name,
-                                 version,
-                                 id: id.clone(),
-                                 is_enabled: true, // Hard to determine without parsing Preferences
-                             });
-                         }
-                     }
-                }
-            }
+            extensions.extend(read_profile_extensions(Path::new(profile_path), Path::new("/"))?);
         }
         Ok(extensions)
     }
-    fn list_displays(&self) -> Result<Vec<DisplayInfo>, String> {
-        let com_con = COMLibrary::new().map_err(|e| e.to_string())?;
+    fn list_displays(&self) -> Result<Vec<DisplayInfo>, ProbeError> {
+        let com_con = COMLibrary::new().map_err(|e| ProbeError::os("failed to initialize COM", e))?;
         let wmi_con = WMIConnection::with_namespace_path("ROOT\\CIMV2", com_con)
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| ProbeError::os("failed to connect to WMI namespace ROOT\\CIMV2", e))?;
 
         // Query Win32_PnPEntity where Service = 'monitor'
         let results: Vec<PnPDevice> = wmi_con
             .raw_query("SELECT Name, DeviceID, Service, Status FROM Win32_PnPEntity WHERE Service = 'monitor'")
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| ProbeError::os("WMI query failed", e))?;
 
         let mut displays = Vec::new();
         for device in results {
             if let Some(status) = &device.status {
                 if status != "OK" { continue; }
             }
+            if let Some(service) = &device.service {
+                if !service.eq_ignore_ascii_case("monitor") { continue; }
+            }
 
             displays.push(DisplayInfo {
                 name: device.name.unwrap_or_else(|| "Unknown Display".to_string()),
                 device_id: device.device_i_d.unwrap_or_default(),
                 is_primary: false, // Hard to determine primary via simple PnPEntity check
+                is_enabled: true,
+                ..Default::default()
             });
         }
         Ok(displays)
     }
 
-    fn check_vm(&self) -> Result<bool, String> {
-        let com_con = COMLibrary::new().map_err(|e| e.to_string())?;
+    fn check_vm(&self) -> Result<bool, ProbeError> {
+        let com_con = COMLibrary::new().map_err(|e| ProbeError::os("failed to initialize COM", e))?;
         let wmi_con = WMIConnection::with_namespace_path("ROOT\\CIMV2", com_con)
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| ProbeError::os("failed to connect to WMI namespace ROOT\\CIMV2", e))?;
         
         let results: Vec<ComputerSystem> = wmi_con
             .raw_query("SELECT Manufacturer, Model FROM Win32_ComputerSystem")
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| ProbeError::os("WMI query failed", e))?;
 
         if let Some(system) = results.first() {
             let manufacturer = system.manufacturer.as_deref().unwrap_or("").to_lowercase();
@@ -233,19 +241,21 @@ impl SystemProfiler for WindowsProfiler {
         Ok(false)
     }
 
-    fn list_network_adapters(&self) -> Result<Vec<NetworkAdapterInfo>, String> {
-        let com_con = COMLibrary::new().map_err(|e| e.to_string())?;
+    fn list_network_adapters(&self) -> Result<Vec<NetworkAdapterInfo>, ProbeError> {
+        let com_con = COMLibrary::new().map_err(|e| ProbeError::os("failed to initialize COM", e))?;
         let wmi_con = WMIConnection::with_namespace_path("ROOT\\CIMV2", com_con)
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| ProbeError::os("failed to connect to WMI namespace ROOT\\CIMV2", e))?;
 
         // NetConnectionStatus = 2 (Connected)
         let results: Vec<Win32NetworkAdapter> = wmi_con
-            .raw_query("SELECT Name, Description, MACAddress, NetConnectionStatus FROM Win32_NetworkAdapter WHERE NetConnectionStatus = 2")
-            .map_err(|e| e.to_string())?;
+            .raw_query("SELECT Name, Description, MACAddress, NetConnectionStatus, ServiceName FROM Win32_NetworkAdapter WHERE NetConnectionStatus = 2")
+            .map_err(|e| ProbeError::os("WMI query failed", e))?;
 
         let mut adapters = Vec::new();
         for adapter in results {
             adapters.push(NetworkAdapterInfo {
+                kind: adapter_kind(adapter.service_name.as_deref()),
+                is_up: adapter.net_connection_status == Some(2),
                 name: adapter.name,
                 description: adapter.description.unwrap_or_default(),
                 mac_address: adapter.m_a_c_address.unwrap_or_default(),
@@ -254,35 +264,11 @@ impl SystemProfiler for WindowsProfiler {
         Ok(adapters)
     }
 
-    fn parse_hosts_file(&self) -> Result<Vec<HostsEntry>, String> {
-        let hosts_path = PathBuf::from("C:\\Windows\\System32\\drivers\\etc\\hosts");
-        if !hosts_path.exists() {
-            return Ok(vec![]);
-        }
-
-        let file = File::open(hosts_path).map_err(|e| e.to_string())?;
-        let reader = BufReader::new(file);
-        let mut entries = Vec::new();
-
-        for line in reader.lines() {
-            let line = line.map_err(|e| e.to_string())?;
-            let trimmed = line.trim();
-            if trimmed.is_empty() || trimmed.starts_with('#') {
-                continue;
-            }
-
-            let parts: Vec<&str> = trimmed.split_whitespace().collect();
-            if parts.len() >= 2 {
-                entries.push(HostsEntry {
-                    ip: parts[0].to_string(),
-                    domain: parts[1].to_string(),
-                });
-            }
-        }
-        Ok(entries)
+    fn parse_hosts_file(&self) -> Result<Vec<HostsEntry>, ProbeError> {
+        read_hosts_file(Path::new("C:\\Windows\\System32\\drivers\\etc\\hosts"))
     }
 
-    fn get_active_window(&self) -> Result<String, String> {
+    fn get_active_window(&self) -> Result<String, ProbeError> {
         use windows::{
             Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowTextW},
             Win32::Foundation::HWND,
@@ -308,26 +294,37 @@ impl SystemProfiler for WindowsProfiler {
 
 pub struct WindowsScanner;
 impl ProcessScanner for WindowsScanner {
-    fn scan(&self) -> Result<Vec<ProcessInfo>, String> {
-        let com_con = COMLibrary::new().map_err(|e| e.to_string())?;
+    /// WMI gives no user or start time.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new().with(Probe::Processes, Support::Partial)
+    }
+
+    fn scan(&self) -> Result<Vec<ProcessInfo>, ProbeError> {
+        let com_con = COMLibrary::new().map_err(|e| ProbeError::os("failed to initialize COM", e))?;
         let wmi_con = WMIConnection::with_namespace_path("ROOT\\CIMV2", com_con)
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| ProbeError::os("failed to connect to WMI namespace ROOT\\CIMV2", e))?;
 
         let results: Vec<Win32Process> = wmi_con
-            .raw_query("SELECT ProcessId, Name FROM Win32_Process")
-            .map_err(|e| e.to_string())?;
+            .raw_query(
+                "SELECT ProcessId, Name, ExecutablePath, CommandLine, ParentProcessId, SessionId FROM Win32_Process",
+            )
+            .map_err(|e| ProbeError::os("WMI query failed", e))?;
 
         Ok(results.into_iter().map(|p| ProcessInfo {
             pid: p.process_id,
             name: p.name,
+            exe_path: p.executable_path,
+            cmdline: p.command_line,
+            ppid: p.parent_process_id,
+            session_id: p.session_id,
+            ..ProcessInfo::default()
         }).collect())
     }
 }
 
 pub struct WindowsBlocker;
 impl Blocker for WindowsBlocker {
-    fn block(&self, pid: u32) -> bool {
-        println!("Blocking process {} on Windows", pid);
-        true
+    fn block(&self, _process: &ProcessInfo) -> BlockResult {
+        BlockResult::Unsupported
     }
 }